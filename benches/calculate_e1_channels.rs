@@ -0,0 +1,84 @@
+// Compares the single-pass channel search, and the search seeded at the square-root
+// staffing estimate, against the original quadratic scan.
+//
+// Run with `cargo bench --bench calculate_e1_channels`.
+
+use erlang_e1::{calculate_e1_channels, calculate_e1_channels_from};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// `erlang_b` as it was before the single-pass rewrite: the inverse recurrence over all
+/// channels, O(N) per call.
+fn erlang_b_original(traffic: f64, channels: u32) -> f64 {
+    let mut inverse_b = 1.0;
+
+    for n in 1..=channels {
+        inverse_b = 1.0 + inverse_b * (n as f64 / traffic);
+    }
+
+    1.0 / inverse_b
+}
+
+/// The channel search before the single-pass rewrite: `erlang_b_original` from scratch per
+/// candidate.
+fn calculate_e1_channels_quadratic(
+    traffic: f64,
+    blocking_probability: f64,
+    channels_max: u32,
+) -> Option<u32> {
+    let mut channels = 1;
+
+    while channels < channels_max {
+        if erlang_b_original(traffic, channels) <= blocking_probability {
+            return Some(channels);
+        }
+        channels += 1;
+    }
+
+    None
+}
+
+fn time<F: FnMut() -> Option<u32>>(iterations: u32, mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        black_box(f());
+    }
+    start.elapsed() / iterations
+}
+
+fn main() {
+    println!(
+        "{:>10} {:>10} {:>14} {:>14} {:>14} {:>9}",
+        "traffic", "channels", "quadratic", "single-pass", "with estimate", "speedup"
+    );
+
+    for &traffic in &[10.0, 100.0, 200.0, 500.0, 1_000.0, 5_000.0] {
+        let iterations = if traffic > 1_000.0 { 3 } else { 50 };
+        let gos = black_box(0.01);
+        let channels = calculate_e1_channels(traffic, gos, 100_000);
+        assert_eq!(
+            calculate_e1_channels_quadratic(traffic, gos, 100_000),
+            channels
+        );
+
+        let quadratic = time(iterations, || {
+            calculate_e1_channels_quadratic(black_box(traffic), gos, 100_000)
+        });
+        let single = time(iterations * 20, || {
+            calculate_e1_channels(black_box(traffic), gos, 100_000)
+        });
+        let estimated = time(iterations * 20, || {
+            calculate_e1_channels_from(black_box(traffic), gos, 100_000, None)
+        });
+
+        println!(
+            "{:>10} {:>10} {:>14?} {:>14?} {:>14?} {:>8.0}x",
+            traffic,
+            channels.unwrap_or(0),
+            quadratic,
+            single,
+            estimated,
+            quadratic.as_secs_f64() / single.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,242 @@
+// SIP trunk / VoIP bandwidth dimensioning from a concurrent call count.
+
+use crate::{try_calculate_e1_channels, ErlangError};
+
+/// Combined IPv4 (20 bytes), UDP (8 bytes) and RTP (12 bytes) header size.
+const IP_UDP_RTP_HEADER_BYTES: f64 = 40.0;
+
+/// Compressed RTP header size (RFC 2508) without the UDP checksum.
+const CRTP_HEADER_BYTES: f64 = 2.0;
+
+/// SRTP authentication tag size for HMAC-SHA1-80.
+const SRTP_AUTH_TAG_BYTES: f64 = 10.0;
+
+/// Voice codecs with their payload bit rates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    /// G.711 A-law/µ-law PCM at 64 kbit/s.
+    G711,
+    /// G.729 CS-ACELP at 8 kbit/s.
+    G729,
+    /// G.722 wideband at 64 kbit/s.
+    G722,
+    /// Opus at a configured payload bit rate in bit/s.
+    Opus {
+        /// Payload bit rate in bit/s.
+        bitrate: u32,
+    },
+}
+
+impl Codec {
+    /// Payload bit rate of the codec in bit/s.
+    pub fn bitrate(self) -> u32 {
+        match self {
+            Codec::G711 | Codec::G722 => 64_000,
+            Codec::G729 => 8_000,
+            Codec::Opus { bitrate } => bitrate,
+        }
+    }
+}
+
+/// Layer 2 encapsulations with their per-frame overhead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer2 {
+    /// Ethernet II: 14-byte header and 4-byte FCS.
+    Ethernet,
+    /// Ethernet with an 802.1Q VLAN tag.
+    Dot1Q,
+    /// PPP over Ethernet: Ethernet plus 6-byte PPPoE and 2-byte PPP headers.
+    PPPoE,
+    /// MPLS over Ethernet with a stack of 4-byte labels.
+    Mpls {
+        /// Number of labels in the stack.
+        labels: u32,
+    },
+}
+
+impl Layer2 {
+    /// Layer 2 overhead per frame in bytes.
+    pub fn overhead_bytes(self) -> u32 {
+        match self {
+            Layer2::Ethernet => 18,
+            Layer2::Dot1Q => 22,
+            Layer2::PPPoE => 26,
+            Layer2::Mpls { labels } => 18 + 4 * labels,
+        }
+    }
+}
+
+/// Packetisation and encapsulation settings of a single voice call.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VoipCall {
+    /// The voice codec.
+    pub codec: Codec,
+    /// Packetisation interval in milliseconds.
+    pub packetization_ms: f64,
+    /// The layer 2 encapsulation.
+    pub layer2: Layer2,
+    /// Whether RTP/UDP/IP headers are compressed (cRTP).
+    pub crtp: bool,
+    /// Whether the media is protected with SRTP.
+    pub srtp: bool,
+}
+
+impl VoipCall {
+    /// Creates a call with 20 ms packetisation, without cRTP or SRTP.
+    pub fn new(codec: Codec, layer2: Layer2) -> Self {
+        VoipCall {
+            codec,
+            packetization_ms: 20.0,
+            layer2,
+            crtp: false,
+            srtp: false,
+        }
+    }
+
+    /// Voice payload carried in each packet, in bytes.
+    pub fn payload_bytes(&self) -> f64 {
+        self.codec.bitrate() as f64 * self.packetization_ms / 8_000.0
+    }
+
+    /// Number of packets sent per second in each direction.
+    pub fn packets_per_second(&self) -> f64 {
+        1_000.0 / self.packetization_ms
+    }
+
+    /// Size of each frame on the wire, in bytes, including all overheads.
+    pub fn frame_bytes(&self) -> f64 {
+        let header = if self.crtp {
+            CRTP_HEADER_BYTES
+        } else {
+            IP_UDP_RTP_HEADER_BYTES
+        };
+        let srtp = if self.srtp { SRTP_AUTH_TAG_BYTES } else { 0.0 };
+        self.payload_bytes() + header + srtp + self.layer2.overhead_bytes() as f64
+    }
+
+    /// Bandwidth used by one call in each direction, in bit/s.
+    pub fn bandwidth_bps(&self) -> f64 {
+        self.frame_bytes() * 8.0 * self.packets_per_second()
+    }
+
+    fn validate(&self) -> Result<(), ErlangError> {
+        if !(self.packetization_ms > 0.0 && self.packetization_ms.is_finite()) {
+            return Err(ErlangError::InvalidParameter(
+                "packetization interval must be finite and positive",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Bandwidth requirement of a dimensioned SIP trunk group.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrunkBandwidth {
+    /// Number of concurrent calls the trunk is sized for.
+    pub channels: u32,
+    /// Bandwidth per call in each direction, in bit/s.
+    pub per_call_bps: f64,
+    /// Bandwidth of the whole trunk group in each direction, in bit/s.
+    pub total_bps: f64,
+}
+
+/// Calculates the bandwidth needed to carry a number of concurrent calls.
+///
+/// # Arguments
+/// * `call` - Codec and encapsulation settings of each call.
+/// * `channels` - The number of concurrent calls, e.g. from `calculate_e1_channels`.
+///
+/// # Returns
+/// The per-call and total bandwidth in each direction, or `ErlangError::InvalidParameter`
+/// if the packetisation interval is not finite and positive.
+pub fn trunk_bandwidth(call: &VoipCall, channels: u32) -> Result<TrunkBandwidth, ErlangError> {
+    call.validate()?;
+    let per_call_bps = call.bandwidth_bps();
+    Ok(TrunkBandwidth {
+        channels,
+        per_call_bps,
+        total_bps: per_call_bps * channels as f64,
+    })
+}
+
+/// Dimensions a SIP trunk for a traffic load and returns its bandwidth.
+///
+/// # Arguments
+/// * `traffic` - The traffic load in Erlangs.
+/// * `blocking_probability` - Desired blocking probability (between 0 and 1).
+/// * `channels_max` - Maximum number of concurrent calls to search for.
+/// * `call` - Codec and encapsulation settings of each call.
+///
+/// # Returns
+/// The concurrent call count with its bandwidth, `ErlangError::InvalidParameter` if the
+/// packetisation interval is not finite and positive, or any error from
+/// `try_calculate_e1_channels`.
+pub fn dimension_sip_trunk(
+    traffic: f64,
+    blocking_probability: f64,
+    channels_max: u32,
+    call: &VoipCall,
+) -> Result<TrunkBandwidth, ErlangError> {
+    call.validate()?;
+    let channels = try_calculate_e1_channels(traffic, blocking_probability, channels_max)?;
+    trunk_bandwidth(call, channels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bandwidth_per_call() {
+        // Well-known figures: G.711 over Ethernet is 87.2 kbit/s, G.729 is 31.2 kbit/s.
+        let g711 = VoipCall::new(Codec::G711, Layer2::Ethernet);
+        assert_eq!(g711.payload_bytes(), 160.0);
+        assert!((g711.bandwidth_bps() - 87_200.0).abs() < 1e-6);
+
+        let g729 = VoipCall::new(Codec::G729, Layer2::Ethernet);
+        assert!((g729.bandwidth_bps() - 31_200.0).abs() < 1e-6);
+
+        let vlan = VoipCall::new(Codec::G711, Layer2::Dot1Q);
+        assert!((vlan.bandwidth_bps() - 88_800.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_bandwidth_options() {
+        let mut call = VoipCall::new(Codec::G729, Layer2::PPPoE);
+        call.packetization_ms = 30.0;
+        call.crtp = true;
+        // 30 + 2 + 26 bytes at 33.3 packets per second.
+        assert!((call.bandwidth_bps() - 58.0 * 8.0 * 1_000.0 / 30.0).abs() < 1e-6);
+
+        call.srtp = true;
+        assert!((call.frame_bytes() - 68.0).abs() < 1e-9);
+
+        let opus = VoipCall::new(Codec::Opus { bitrate: 24_000 }, Layer2::Mpls { labels: 2 });
+        assert_eq!(opus.frame_bytes(), 60.0 + 40.0 + 26.0);
+    }
+
+    #[test]
+    fn test_dimension_sip_trunk() {
+        let call = VoipCall::new(Codec::G711, Layer2::Ethernet);
+        let trunk = dimension_sip_trunk(50.0, 0.01, 1_000, &call).unwrap();
+        assert_eq!(trunk.channels, 64);
+        assert!((trunk.total_bps - 64.0 * 87_200.0).abs() < 1e-6);
+        assert_eq!(trunk_bandwidth(&call, 0).unwrap().total_bps, 0.0);
+    }
+
+    #[test]
+    fn test_invalid_packetization() {
+        let mut call = VoipCall::new(Codec::G711, Layer2::Ethernet);
+        for packetization_ms in [0.0, -20.0, f64::NAN, f64::INFINITY] {
+            call.packetization_ms = packetization_ms;
+            assert!(matches!(
+                trunk_bandwidth(&call, 30),
+                Err(ErlangError::InvalidParameter(_))
+            ));
+            assert!(matches!(
+                dimension_sip_trunk(50.0, 0.01, 1_000, &call),
+                Err(ErlangError::InvalidParameter(_))
+            ));
+        }
+    }
+}
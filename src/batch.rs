@@ -0,0 +1,390 @@
+// Batch dimensioning of many routes from a CSV scenario file.
+
+use crate::csv::{data_lines, join_fields, split_line};
+use crate::planning::GrowthModel;
+use crate::profile::{TimeUnit, TrafficProfile};
+use crate::trunk::{dimension_trunks, TrunkType};
+use crate::{erlang_b, ErlangError};
+use std::fmt;
+use std::str::FromStr;
+
+/// Columns of a scenario file, matched case-insensitively against the header row.
+///
+/// Each row needs `gos` and either `traffic` in Erlangs or `users`, `holding_time` in
+/// seconds and one of `bhca` with `completion_ratio` or `calls_per_user` (optionally
+/// `active_percent`). `route`,
+/// `trunk` (default `e1-pri`), `growth` (e.g. `0.1` for 10% per period) and `periods`
+/// (default 1) are optional, as is `max` to override the channel search limit. Other
+/// columns are ignored.
+pub const COLUMNS: [&str; 13] = [
+    "route",
+    "traffic",
+    "users",
+    "bhca",
+    "completion_ratio",
+    "calls_per_user",
+    "holding_time",
+    "active_percent",
+    "gos",
+    "trunk",
+    "growth",
+    "periods",
+    "max",
+];
+
+/// Errors that stop a whole scenario file from being dimensioned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchError {
+    /// A required column is not present in the header row.
+    MissingColumn(String),
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::MissingColumn(name) => write!(f, "column '{name}' not found in header"),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+/// A route that could not be dimensioned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    /// 1-based line number in the input.
+    pub line: usize,
+    /// The route name, empty if the row has none.
+    pub route: String,
+    /// Description of the problem.
+    pub reason: String,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for RowError {}
+
+/// Dimensioning of one route.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteResult {
+    /// 1-based line number in the input.
+    pub line: usize,
+    /// The route name, empty if the row has none.
+    pub route: String,
+    /// Design traffic in Erlangs, after growth.
+    pub traffic: f64,
+    /// The trunk type providing the spans.
+    pub trunk_type: TrunkType,
+    /// Channels required, from `calculate_e1_channels`.
+    pub channels: u32,
+    /// Spans required to carry the channels.
+    pub spans: u32,
+    /// Bearer channels provided by the spans.
+    pub installed_channels: u32,
+    /// Erlang B blocking on the installed channels.
+    pub blocking: f64,
+    /// Carried traffic per installed channel (between 0 and 1).
+    pub utilization: f64,
+}
+
+/// Column positions resolved from the header row.
+struct Layout {
+    positions: Vec<Option<usize>>,
+}
+
+impl Layout {
+    fn new(header: &[String]) -> Self {
+        let positions = COLUMNS
+            .iter()
+            .map(|name| {
+                header
+                    .iter()
+                    .position(|field| field.eq_ignore_ascii_case(name))
+            })
+            .collect();
+        Layout { positions }
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.position(name).is_some()
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        let column = COLUMNS.iter().position(|column| *column == name)?;
+        self.positions[column]
+    }
+
+    /// The trimmed value of a column in a row, or `None` if the column or value is absent.
+    fn value<'a>(&self, fields: &'a [String], name: &str) -> Option<&'a str> {
+        self.position(name)
+            .and_then(|index| fields.get(index))
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+
+    fn parse<T: FromStr>(&self, fields: &[String], name: &str) -> Result<Option<T>, String> {
+        self.value(fields, name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("invalid {name} '{value}'"))
+            })
+            .transpose()
+    }
+
+    fn required<T: FromStr>(&self, fields: &[String], name: &str) -> Result<T, String> {
+        self.parse(fields, name)?
+            .ok_or_else(|| format!("missing {name}"))
+    }
+}
+
+fn offered_traffic(layout: &Layout, fields: &[String]) -> Result<f64, String> {
+    if let Some(traffic) = layout.parse(fields, "traffic")? {
+        return Ok(traffic);
+    }
+
+    let mut profile = TrafficProfile::new(layout.required(fields, "users")?)
+        .holding_time(layout.required(fields, "holding_time")?, TimeUnit::Seconds);
+    profile = match (
+        layout.parse(fields, "bhca")?,
+        layout.parse(fields, "calls_per_user")?,
+    ) {
+        (Some(bhca), None) => {
+            profile.bhca_per_user(bhca, layout.required(fields, "completion_ratio")?)
+        }
+        (None, Some(calls)) => profile.calls_per_user(calls),
+        _ => {
+            return Err("expected traffic, or users with one of bhca or calls_per_user".to_string())
+        }
+    };
+    if let Some(percent) = layout.parse(fields, "active_percent")? {
+        profile = profile.active_users_percent(percent);
+    }
+    profile.traffic().map_err(|error| error.to_string())
+}
+
+fn dimension_row(
+    layout: &Layout,
+    fields: &[String],
+    line: usize,
+    channels_max: u32,
+) -> Result<RouteResult, String> {
+    let base_traffic = offered_traffic(layout, fields)?;
+    let growth: f64 = layout.parse(fields, "growth")?.unwrap_or(0.0);
+    let periods: f64 = layout.parse(fields, "periods")?.unwrap_or(1.0);
+    if !(growth > -1.0 && growth.is_finite()) {
+        return Err(format!("invalid growth {growth}: must be above -1"));
+    }
+    if !(periods >= 0.0 && periods.is_finite()) {
+        return Err(format!("invalid periods {periods}: must be non-negative"));
+    }
+    let traffic = GrowthModel::Compound(growth).traffic(base_traffic, periods);
+
+    let gos = layout.required(fields, "gos")?;
+    let trunk_type = match layout.value(fields, "trunk") {
+        Some(name) => name
+            .parse()
+            .map_err(|error: ErlangError| error.to_string())?,
+        None => TrunkType::E1Pri,
+    };
+    let channels_max = layout.parse(fields, "max")?.unwrap_or(channels_max);
+    let plan = dimension_trunks(traffic, gos, channels_max, trunk_type)
+        .map_err(|error| error.to_string())?;
+
+    let installed_channels = plan.bearer_capacity;
+    let blocking = erlang_b(traffic, installed_channels);
+    let utilization = if installed_channels == 0 {
+        0.0
+    } else {
+        traffic * (1.0 - blocking) / installed_channels as f64
+    };
+
+    Ok(RouteResult {
+        line,
+        route: layout
+            .value(fields, "route")
+            .unwrap_or_default()
+            .to_string(),
+        traffic,
+        trunk_type,
+        channels: plan.channels,
+        spans: plan.spans,
+        installed_channels,
+        blocking,
+        utilization,
+    })
+}
+
+/// Dimensions every route of a CSV scenario file. Invalid rows are reported one by one
+/// without stopping the rest of the batch.
+///
+/// # Arguments
+/// * `input` - The CSV document with a header row naming the `COLUMNS` used. Blank lines
+///   and lines starting with `#` are skipped.
+/// * `channels_max` - Maximum number of channels to search for, unless a row sets `max`.
+///
+/// # Returns
+/// One result per data row in input order, with a `RowError` for each row that could not
+/// be dimensioned, or `BatchError::MissingColumn` if the header
+/// has no `gos` column or neither a `traffic` nor a `users` column.
+pub fn dimension_routes(
+    input: &str,
+    channels_max: u32,
+) -> Result<Vec<Result<RouteResult, RowError>>, BatchError> {
+    let mut lines = data_lines(input);
+    let header = lines
+        .next()
+        .map(|(_, line)| split_line(line, ','))
+        .unwrap_or_default();
+    let layout = Layout::new(&header);
+    if !layout.has("gos") {
+        return Err(BatchError::MissingColumn("gos".to_string()));
+    }
+    if !layout.has("traffic") && !layout.has("users") {
+        return Err(BatchError::MissingColumn("traffic".to_string()));
+    }
+
+    Ok(lines
+        .map(|(line, text)| {
+            let fields = split_line(text, ',');
+            dimension_row(&layout, &fields, line, channels_max).map_err(|reason| RowError {
+                line,
+                route: layout
+                    .value(&fields, "route")
+                    .unwrap_or_default()
+                    .to_string(),
+                reason,
+            })
+        })
+        .collect())
+}
+
+/// Columns of the batch output, in the order of `result_fields`.
+pub const RESULT_COLUMNS: [&str; 10] = [
+    "line",
+    "route",
+    "traffic",
+    "trunk",
+    "channels",
+    "spans",
+    "installed_channels",
+    "blocking",
+    "utilization",
+    "error",
+];
+
+/// Formats one batch result as text fields, one per `RESULT_COLUMNS` entry. Rows that
+/// failed leave the results empty and fill `error`.
+pub fn result_fields(result: &Result<RouteResult, RowError>) -> Vec<String> {
+    match result {
+        Ok(route) => vec![
+            route.line.to_string(),
+            route.route.clone(),
+            route.traffic.to_string(),
+            route.trunk_type.to_string(),
+            route.channels.to_string(),
+            route.spans.to_string(),
+            route.installed_channels.to_string(),
+            route.blocking.to_string(),
+            route.utilization.to_string(),
+            String::new(),
+        ],
+        Err(error) => {
+            let mut fields = vec![error.line.to_string(), error.route.clone()];
+            fields.resize(RESULT_COLUMNS.len() - 1, String::new());
+            fields.push(error.reason.clone());
+            fields
+        }
+    }
+}
+
+/// Writes batch results as CSV with a `RESULT_COLUMNS` header and one line per route, in
+/// the order given.
+pub fn write_results(results: &[Result<RouteResult, RowError>]) -> String {
+    let mut output = RESULT_COLUMNS.join(",") + "\n";
+    for result in results {
+        output += &(join_fields(&result_fields(result), ',') + "\n");
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculate_e1_channels;
+
+    const SCENARIO: &str = "\
+route,traffic,users,calls_per_user,holding_time,gos,trunk,growth
+# route, Erlangs or users, grade of service, trunk type, growth
+london-paris,50,,,,0.01,e1-pri,
+\"rome, north\",,500,2,180,0.01,t1-pri,0.1
+bad-gos,20,,,,1.5,,
+bad-trunk,20,,,,0.01,e2,
+no-traffic,,,,,0.01,,
+";
+
+    #[test]
+    fn test_dimension_routes() {
+        let results = dimension_routes(SCENARIO, 10_000).unwrap();
+        assert_eq!(results.len(), 5);
+
+        let london = results[0].as_ref().unwrap();
+        assert_eq!(london.line, 3);
+        assert_eq!(
+            london.channels,
+            calculate_e1_channels(50.0, 0.01, 10_000).unwrap()
+        );
+        assert_eq!(london.spans, 3);
+        assert_eq!(london.installed_channels, 90);
+        assert_eq!(london.blocking, erlang_b(50.0, 90));
+        assert!((london.utilization - 50.0 * (1.0 - london.blocking) / 90.0).abs() < 1e-15);
+
+        // 500 users × 2 calls × 3 minutes = 50 Erlangs, plus 10% growth.
+        let rome = results[1].as_ref().unwrap();
+        assert_eq!(rome.route, "rome, north");
+        assert!((rome.traffic - 55.0).abs() < 1e-9);
+        assert_eq!(rome.trunk_type, TrunkType::T1Pri);
+        assert_eq!(rome.spans, rome.channels.div_ceil(23));
+
+        let reasons: Vec<String> = results[2..]
+            .iter()
+            .map(|result| result.as_ref().unwrap_err().to_string())
+            .collect();
+        assert_eq!(
+            reasons,
+            [
+                "line 5: invalid blocking probability 1.5: must be between 0 and 1",
+                "line 6: invalid parameter: unknown trunk type (expected e1-pri, e1-cas, t1-pri, t1-cas or j1)",
+                "line 7: missing users",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_columns() {
+        assert_eq!(
+            dimension_routes("route,traffic\na,10\n", 100),
+            Err(BatchError::MissingColumn("gos".to_string()))
+        );
+        assert_eq!(
+            dimension_routes("route,gos\na,0.01\n", 100),
+            Err(BatchError::MissingColumn("traffic".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_write_results() {
+        let results = dimension_routes(SCENARIO, 10_000).unwrap();
+        let output = write_results(&results);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert!(lines[1].starts_with("3,london-paris,50,e1-pri,64,3,90,"));
+        assert!(lines[2].starts_with("4,\"rome, north\","));
+        assert_eq!(lines[5], "7,no-traffic,,,,,,,,missing users");
+        assert_eq!(split_line(lines[4], ',').len(), 10);
+    }
+}
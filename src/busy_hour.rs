@@ -0,0 +1,392 @@
+// ITU-T E.500 busy-hour methods (TCBH, ADPH, FDMH) over multi-day interval data.
+
+use crate::cdr::TrafficInterval;
+use crate::{try_calculate_e1_channels, ErlangError};
+use std::collections::BTreeMap;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const SECONDS_PER_HOUR: u32 = 3_600;
+
+/// E.500 method used to derive a design traffic figure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusyHourMethod {
+    /// Time Consistent Busy Hour: the same clock hour on every day, chosen for the
+    /// highest mean traffic.
+    Tcbh,
+    /// Average Daily Peak Hour: the mean of each day's own peak hour.
+    Adph,
+    /// Fixed Daily Measurement Hour: a clock hour fixed in advance.
+    Fdmh,
+}
+
+/// Traffic one day contributes to a design figure.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DayTraffic {
+    /// Start of the day in Unix seconds (UTC midnight).
+    pub day: i64,
+    /// Start of the hour used for this day in Unix seconds.
+    pub hour_start: i64,
+    /// Mean offered traffic over that hour in Erlangs.
+    pub traffic: f64,
+}
+
+/// Design traffic derived with one of the E.500 busy-hour methods.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesignTraffic {
+    /// The method used.
+    pub method: BusyHourMethod,
+    /// Design traffic in Erlangs, the mean over the contributing days.
+    pub traffic: f64,
+    /// Start of the chosen hour in seconds after midnight, or `None` for ADPH where
+    /// every day uses its own peak hour.
+    pub hour: Option<u32>,
+    /// The days that contributed and the traffic each one measured.
+    pub days: Vec<DayTraffic>,
+}
+
+impl DesignTraffic {
+    /// Calculates the channels required to carry the design traffic.
+    ///
+    /// # Arguments
+    /// * `blocking_probability` - Desired blocking probability (between 0 and 1).
+    /// * `channels_max` - Maximum number of channels to search for.
+    ///
+    /// # Returns
+    /// The number of channels, or any error from `try_calculate_e1_channels`.
+    pub fn calculate_channels(
+        &self,
+        blocking_probability: f64,
+        channels_max: u32,
+    ) -> Result<u32, ErlangError> {
+        try_calculate_e1_channels(self.traffic, blocking_probability, channels_max)
+    }
+}
+
+/// Offered traffic per measurement slot of each day.
+struct DailySlots {
+    length: u32,
+    days: BTreeMap<i64, Vec<Option<f64>>>,
+}
+
+impl DailySlots {
+    fn new(intervals: &[TrafficInterval]) -> Option<Self> {
+        let length = intervals.first()?.length;
+        if length == 0 || !SECONDS_PER_HOUR.is_multiple_of(length) {
+            return None;
+        }
+
+        let slots_per_day = (SECONDS_PER_DAY / length as i64) as usize;
+        let mut days = BTreeMap::new();
+        for interval in intervals
+            .iter()
+            .filter(|interval| interval.length == length)
+        {
+            let day = interval.start.div_euclid(SECONDS_PER_DAY) * SECONDS_PER_DAY;
+            let slot = ((interval.start - day) / length as i64) as usize;
+            days.entry(day).or_insert_with(|| vec![None; slots_per_day])[slot] =
+                Some(interval.offered);
+        }
+
+        Some(DailySlots { length, days })
+    }
+
+    fn window(&self) -> usize {
+        (SECONDS_PER_HOUR / self.length) as usize
+    }
+
+    /// Hour start offsets (in seconds after midnight) that fit inside a day.
+    fn hour_offsets(&self) -> impl Iterator<Item = u32> + '_ {
+        let last = (SECONDS_PER_DAY as u32 - SECONDS_PER_HOUR) / self.length;
+        (0..=last).map(move |slot| slot * self.length)
+    }
+
+    /// Mean traffic of the hour starting at `offset` on `day`, if every slot was measured.
+    fn hour_traffic(&self, day: &[Option<f64>], offset: u32) -> Option<f64> {
+        let first = (offset / self.length) as usize;
+        let slots = day.get(first..first + self.window())?;
+        let total = slots
+            .iter()
+            .try_fold(0.0, |total, slot| slot.map(|v| total + v))?;
+        Some(total / self.window() as f64)
+    }
+
+    /// The days TCBH compares hours over: the days measured in full, or every day if none
+    /// was.
+    fn common_days(&self) -> Vec<(i64, &[Option<f64>])> {
+        let complete: Vec<(i64, &[Option<f64>])> = self
+            .days
+            .iter()
+            .filter(|(_, slots)| slots.iter().all(Option::is_some))
+            .map(|(&day, slots)| (day, slots.as_slice()))
+            .collect();
+        if !complete.is_empty() {
+            return complete;
+        }
+        self.days
+            .iter()
+            .map(|(&day, slots)| (day, slots.as_slice()))
+            .collect()
+    }
+
+    /// Per-day traffic for a fixed hour, skipping days where it was not fully measured.
+    fn fixed_hour(&self, offset: u32) -> Vec<DayTraffic> {
+        self.days
+            .iter()
+            .filter_map(|(&day, slots)| {
+                self.hour_traffic(slots, offset).map(|traffic| DayTraffic {
+                    day,
+                    hour_start: day + offset as i64,
+                    traffic,
+                })
+            })
+            .collect()
+    }
+}
+
+fn mean(days: &[DayTraffic]) -> f64 {
+    days.iter().map(|day| day.traffic).sum::<f64>() / days.len() as f64
+}
+
+/// Calculates the Time Consistent Busy Hour: the clock hour with the highest traffic
+/// averaged over all days. With 15-minute data the hour may start at any quarter hour.
+///
+/// Every hour is compared over the same days. Days that were only partly measured (such
+/// as the first and last day of a capture) are left out when at least one day was
+/// measured in full; otherwise every day is used and only the hours measured on all of
+/// them are candidates.
+///
+/// # Arguments
+/// * `intervals` - Interval measurements over several days, e.g. from `traffic_intervals`.
+///
+/// # Returns
+/// The design traffic with the chosen hour, or `None` if no hour was fully measured on
+/// every day compared.
+pub fn time_consistent_busy_hour(intervals: &[TrafficInterval]) -> Option<DesignTraffic> {
+    let slots = DailySlots::new(intervals)?;
+    let common_days = slots.common_days();
+
+    slots
+        .hour_offsets()
+        .filter_map(|offset| {
+            let days = common_days
+                .iter()
+                .map(|&(day, day_slots)| {
+                    slots
+                        .hour_traffic(day_slots, offset)
+                        .map(|traffic| DayTraffic {
+                            day,
+                            hour_start: day + offset as i64,
+                            traffic,
+                        })
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some((offset, days))
+        })
+        .map(|(offset, days)| DesignTraffic {
+            method: BusyHourMethod::Tcbh,
+            traffic: mean(&days),
+            hour: Some(offset),
+            days,
+        })
+        .fold(None, |best: Option<DesignTraffic>, candidate| match best {
+            Some(best) if best.traffic >= candidate.traffic => Some(best),
+            _ => Some(candidate),
+        })
+}
+
+/// Calculates the Average Daily Peak Hour: each day's busiest hour, averaged over the days.
+///
+/// # Arguments
+/// * `intervals` - Interval measurements over several days, e.g. from `traffic_intervals`.
+///
+/// # Returns
+/// The design traffic with each day's peak hour, or `None` if no hour was fully measured.
+pub fn average_daily_peak_hour(intervals: &[TrafficInterval]) -> Option<DesignTraffic> {
+    let slots = DailySlots::new(intervals)?;
+
+    let days: Vec<DayTraffic> = slots
+        .days
+        .iter()
+        .filter_map(|(&day, day_slots)| {
+            slots
+                .hour_offsets()
+                .filter_map(|offset| {
+                    slots
+                        .hour_traffic(day_slots, offset)
+                        .map(|traffic| DayTraffic {
+                            day,
+                            hour_start: day + offset as i64,
+                            traffic,
+                        })
+                })
+                .fold(None, |best: Option<DayTraffic>, candidate| match best {
+                    Some(best) if best.traffic >= candidate.traffic => Some(best),
+                    _ => Some(candidate),
+                })
+        })
+        .collect();
+
+    if days.is_empty() {
+        return None;
+    }
+
+    Some(DesignTraffic {
+        method: BusyHourMethod::Adph,
+        traffic: mean(&days),
+        hour: None,
+        days,
+    })
+}
+
+/// Calculates the Fixed Daily Measurement Hour: traffic in a predetermined clock hour,
+/// averaged over the days.
+///
+/// # Arguments
+/// * `intervals` - Interval measurements over several days, e.g. from `traffic_intervals`.
+/// * `hour_start` - Start of the measurement hour in seconds after midnight (UTC).
+///
+/// # Returns
+/// The design traffic for that hour, or `None` if it was not fully measured on any day.
+pub fn fixed_daily_measurement_hour(
+    intervals: &[TrafficInterval],
+    hour_start: u32,
+) -> Option<DesignTraffic> {
+    let slots = DailySlots::new(intervals)?;
+    if !hour_start.is_multiple_of(slots.length) {
+        return None;
+    }
+
+    let days = slots.fixed_hour(hour_start);
+    if days.is_empty() {
+        return None;
+    }
+
+    Some(DesignTraffic {
+        method: BusyHourMethod::Fdmh,
+        traffic: mean(&days),
+        hour: Some(hour_start),
+        days,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two days of hourly data: day one peaks at 10:00, day two at 14:00.
+    fn two_days() -> Vec<TrafficInterval> {
+        (0..48)
+            .map(|hour| {
+                let offered = match hour {
+                    10 => 30.0,
+                    14 => 20.0,
+                    34 => 10.0,
+                    38 => 40.0,
+                    _ => 5.0,
+                };
+                TrafficInterval {
+                    start: 1_704_067_200 + hour * 3_600,
+                    length: 3_600,
+                    attempts: 0,
+                    answered: 0,
+                    carried: offered,
+                    offered,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_time_consistent_busy_hour() {
+        let design = time_consistent_busy_hour(&two_days()).unwrap();
+        assert_eq!(design.hour, Some(14 * 3_600));
+        assert_eq!(design.traffic, 30.0);
+        assert_eq!(design.days.len(), 2);
+        assert_eq!(design.calculate_channels(0.01, 100), Ok(42));
+    }
+
+    #[test]
+    fn test_time_consistent_busy_hour_partial_day() {
+        // A single busy 23:00 on a partly measured day, then three full days peaking at
+        // 10:00. Over four days 23:00 would average 16.25 Erlangs against 10:00's 10.
+        let day = 1_704_067_200;
+        let hourly = |start: i64, offered: f64| TrafficInterval {
+            start,
+            length: 3_600,
+            attempts: 0,
+            answered: 0,
+            carried: offered,
+            offered,
+        };
+        let mut intervals = vec![hourly(day + 23 * 3_600, 50.0)];
+        for hour in 24..96 {
+            let offered = if hour % 24 == 10 { 10.0 } else { 5.0 };
+            intervals.push(hourly(day + hour * 3_600, offered));
+        }
+        let design = time_consistent_busy_hour(&intervals).unwrap();
+        assert_eq!(design.hour, Some(10 * 3_600));
+        assert_eq!(design.traffic, 10.0);
+        assert_eq!(design.days.len(), 3);
+        assert!(design.days.iter().all(|entry| entry.day > day));
+
+        // Without a complete day, only the hours measured on every day are compared: a
+        // capture from 09:00 to 18:00 the next day leaves 09:00 to 17:00, and the 20:00
+        // peak on the first day alone is not a candidate.
+        let partial: Vec<TrafficInterval> = (9..42)
+            .map(|hour| {
+                let offered = match hour {
+                    12 => 20.0,
+                    20 => 90.0,
+                    _ => 5.0,
+                };
+                hourly(day + hour * 3_600, offered)
+            })
+            .collect();
+        let design = time_consistent_busy_hour(&partial).unwrap();
+        assert_eq!(design.days.len(), 2);
+        assert_eq!(design.hour, Some(12 * 3_600));
+        assert_eq!(design.traffic, 12.5);
+        let split: Vec<TrafficInterval> = (9..33)
+            .map(|hour| hourly(day + hour * 3_600, 5.0))
+            .collect();
+        assert!(time_consistent_busy_hour(&split).is_none());
+    }
+
+    #[test]
+    fn test_average_daily_peak_hour() {
+        let design = average_daily_peak_hour(&two_days()).unwrap();
+        assert_eq!(design.hour, None);
+        assert_eq!(design.traffic, 35.0);
+        assert_eq!(design.days[0].hour_start, 1_704_067_200 + 10 * 3_600);
+        assert_eq!(design.days[1].hour_start, 1_704_153_600 + 14 * 3_600);
+    }
+
+    #[test]
+    fn test_fixed_daily_measurement_hour() {
+        let design = fixed_daily_measurement_hour(&two_days(), 10 * 3_600).unwrap();
+        assert_eq!(design.traffic, 20.0);
+        assert!(fixed_daily_measurement_hour(&two_days(), 10 * 3_600 + 60).is_none());
+        assert!(fixed_daily_measurement_hour(&[], 0).is_none());
+    }
+
+    #[test]
+    fn test_quarter_hour_windows() {
+        // A peak straddling the clock hour is found by sliding quarter-hour windows.
+        let intervals: Vec<TrafficInterval> = (0..96)
+            .map(|slot| {
+                let offered = if (38..42).contains(&slot) { 12.0 } else { 2.0 };
+                TrafficInterval {
+                    start: slot * 900,
+                    length: 900,
+                    attempts: 0,
+                    answered: 0,
+                    carried: offered,
+                    offered,
+                }
+            })
+            .collect();
+        let design = time_consistent_busy_hour(&intervals).unwrap();
+        assert_eq!(design.hour, Some(38 * 900));
+        assert_eq!(design.traffic, 12.0);
+    }
+}
@@ -0,0 +1,507 @@
+// Busy-hour traffic measurement from call detail records (CDR) in CSV form.
+
+use crate::csv::{data_lines, split_line};
+use crate::{try_calculate_e1_channels, ErlangError};
+use std::fmt;
+
+/// Reference to a CSV column, either by zero-based position or by header name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Column {
+    /// Zero-based column position.
+    Index(usize),
+    /// Column header name (requires a header row).
+    Name(String),
+}
+
+/// Layout of a CDR file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CdrFormat {
+    /// Column holding the call start time, as Unix seconds or `YYYY-MM-DD HH:MM[:SS]` (UTC).
+    pub start: Column,
+    /// Column holding the call duration, as seconds or `HH:MM:SS` (at most one week).
+    pub duration: Column,
+    /// Column holding the call status, or `None` to treat calls with a duration as answered.
+    pub status: Option<Column>,
+    /// Status values (case-insensitive) that mark an answered call.
+    pub answered_values: Vec<String>,
+    /// Field delimiter.
+    pub delimiter: char,
+    /// Whether the first data line is a header row.
+    pub has_header: bool,
+}
+
+impl Default for CdrFormat {
+    /// `start,duration,status` with a header row and common answered markers.
+    fn default() -> Self {
+        CdrFormat {
+            start: Column::Index(0),
+            duration: Column::Index(1),
+            status: Some(Column::Index(2)),
+            answered_values: ["answered", "answer", "ok", "yes", "true", "1"]
+                .iter()
+                .map(|value| value.to_string())
+                .collect(),
+            delimiter: ',',
+            has_header: true,
+        }
+    }
+}
+
+/// Errors reported while parsing CDR input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CdrError {
+    /// A named column is not present in the header row.
+    MissingColumn(String),
+    /// A record could not be parsed.
+    InvalidRecord {
+        /// 1-based line number in the input.
+        line: usize,
+        /// Description of the problem.
+        reason: String,
+    },
+}
+
+impl fmt::Display for CdrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CdrError::MissingColumn(name) => write!(f, "column '{name}' not found in header"),
+            CdrError::InvalidRecord { line, reason } => write!(f, "line {line}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for CdrError {}
+
+/// A single call record.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CallRecord {
+    /// Call start time in Unix seconds (UTC).
+    pub start: i64,
+    /// Call holding time in seconds.
+    pub duration: f64,
+    /// Whether the call was answered.
+    pub answered: bool,
+}
+
+/// Measurement interval length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntervalLength {
+    /// 15-minute intervals.
+    Minutes15,
+    /// 60-minute intervals.
+    Minutes60,
+}
+
+impl IntervalLength {
+    /// Interval length in seconds.
+    pub fn seconds(self) -> u32 {
+        match self {
+            IntervalLength::Minutes15 => 900,
+            IntervalLength::Minutes60 => 3_600,
+        }
+    }
+}
+
+/// Traffic measured over one interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrafficInterval {
+    /// Interval start in Unix seconds, aligned to the interval length.
+    pub start: i64,
+    /// Interval length in seconds.
+    pub length: u32,
+    /// Call attempts starting in the interval.
+    pub attempts: u32,
+    /// Answered calls starting in the interval.
+    pub answered: u32,
+    /// Carried traffic in Erlangs: answered holding time inside the interval / interval length.
+    pub carried: f64,
+    /// Offered traffic in Erlangs: carried traffic plus failed attempts valued at the
+    /// mean holding time of answered calls.
+    pub offered: f64,
+}
+
+/// The busiest 60-minute window of a measurement.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BusyHour {
+    /// Start of the busy hour in Unix seconds.
+    pub start: i64,
+    /// Mean offered traffic over the hour in Erlangs.
+    pub offered: f64,
+    /// Mean carried traffic over the hour in Erlangs.
+    pub carried: f64,
+}
+
+impl BusyHour {
+    /// Calculates the channels required to carry the busy-hour offered traffic.
+    ///
+    /// # Arguments
+    /// * `blocking_probability` - Desired blocking probability (between 0 and 1).
+    /// * `channels_max` - Maximum number of channels to search for.
+    ///
+    /// # Returns
+    /// The number of channels, or any error from `try_calculate_e1_channels`.
+    pub fn calculate_channels(
+        &self,
+        blocking_probability: f64,
+        channels_max: u32,
+    ) -> Result<u32, ErlangError> {
+        try_calculate_e1_channels(self.offered, blocking_probability, channels_max)
+    }
+}
+
+/// Parses CDR records from CSV text.
+///
+/// # Arguments
+/// * `input` - The CSV document. Blank lines and lines starting with `#` are skipped.
+/// * `format` - Column mapping and delimiter.
+///
+/// # Returns
+/// The parsed call records, or the first error encountered.
+pub fn parse_cdr(input: &str, format: &CdrFormat) -> Result<Vec<CallRecord>, CdrError> {
+    let mut lines = data_lines(input);
+    let header = if format.has_header {
+        lines
+            .next()
+            .map(|(_, line)| split_line(line, format.delimiter))
+    } else {
+        None
+    };
+
+    let resolve = |column: &Column| match column {
+        Column::Index(index) => Ok(*index),
+        Column::Name(name) => header
+            .as_ref()
+            .and_then(|fields| {
+                fields
+                    .iter()
+                    .position(|field| field.eq_ignore_ascii_case(name))
+            })
+            .ok_or_else(|| CdrError::MissingColumn(name.clone())),
+    };
+    let start_index = resolve(&format.start)?;
+    let duration_index = resolve(&format.duration)?;
+    let status_index = format.status.as_ref().map(resolve).transpose()?;
+
+    let mut records = Vec::new();
+    for (line, text) in lines {
+        let fields = split_line(text, format.delimiter);
+        let invalid = |reason: String| CdrError::InvalidRecord { line, reason };
+        let field = |index: usize| {
+            fields
+                .get(index)
+                .map(String::as_str)
+                .ok_or_else(|| invalid(format!("missing column {index}")))
+        };
+
+        let start_text = field(start_index)?;
+        let start = parse_timestamp(start_text)
+            .ok_or_else(|| invalid(format!("invalid start time '{start_text}'")))?;
+        let duration_text = field(duration_index)?;
+        let duration = parse_duration(duration_text)
+            .ok_or_else(|| invalid(format!("invalid duration '{duration_text}'")))?;
+        let answered = match status_index {
+            Some(index) => {
+                let status = field(index)?;
+                format
+                    .answered_values
+                    .iter()
+                    .any(|value| value.eq_ignore_ascii_case(status))
+            }
+            None => duration > 0.0,
+        };
+
+        records.push(CallRecord {
+            start,
+            duration: if answered { duration } else { 0.0 },
+            answered,
+        });
+    }
+
+    Ok(records)
+}
+
+/// Longest call duration accepted when parsing, in seconds (one week).
+const MAX_DURATION: f64 = 7.0 * 86_400.0;
+
+/// Most intervals `traffic_intervals` creates: more than two years of 15-minute intervals.
+const MAX_INTERVALS: i64 = 100_000;
+
+/// Calculates offered and carried traffic per interval. Answered calls that span several
+/// intervals contribute their holding time to each interval they overlap.
+///
+/// # Arguments
+/// * `records` - The call records.
+/// * `interval` - The measurement interval length.
+///
+/// # Returns
+/// One entry per interval from the first call start to the last call end, including
+/// intervals without traffic, or empty if there are no records.
+/// `ErlangError::NumericOverflow` if a call end does not fit in Unix seconds, and
+/// `ErlangError::InvalidParameter` if the records span more than 100,000 intervals (as
+/// when start times mix seconds and milliseconds).
+pub fn traffic_intervals(
+    records: &[CallRecord],
+    interval: IntervalLength,
+) -> Result<Vec<TrafficInterval>, ErlangError> {
+    let length = interval.seconds() as i64;
+    let mut ends = Vec::with_capacity(records.len());
+    for record in records {
+        ends.push(
+            record
+                .start
+                .checked_add(record.duration.ceil() as i64)
+                .ok_or(ErlangError::NumericOverflow)?,
+        );
+    }
+    let (first, last) = match (
+        records.iter().map(|record| record.start).min(),
+        ends.into_iter().max(),
+    ) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Ok(Vec::new()),
+    };
+
+    let origin = first.div_euclid(length) * length;
+    let count = last
+        .checked_sub(origin)
+        .map(|span| span / length + 1)
+        .filter(|&count| count <= MAX_INTERVALS)
+        .ok_or(ErlangError::InvalidParameter(
+            "records span more than 100,000 intervals",
+        ))? as usize;
+    let mut intervals: Vec<TrafficInterval> = (0..count)
+        .map(|index| TrafficInterval {
+            start: origin + index as i64 * length,
+            length: length as u32,
+            attempts: 0,
+            answered: 0,
+            carried: 0.0,
+            offered: 0.0,
+        })
+        .collect();
+
+    let answered: Vec<&CallRecord> = records.iter().filter(|record| record.answered).collect();
+    let mean_holding_time = if answered.is_empty() {
+        0.0
+    } else {
+        answered.iter().map(|record| record.duration).sum::<f64>() / answered.len() as f64
+    };
+
+    for record in records {
+        let index = ((record.start - origin) / length) as usize;
+        intervals[index].attempts += 1;
+        if !record.answered {
+            intervals[index].offered += mean_holding_time / length as f64;
+            continue;
+        }
+        intervals[index].answered += 1;
+
+        // Spread the holding time over every interval the call overlaps.
+        let call_start = record.start as f64;
+        let call_end = call_start + record.duration;
+        let mut slot = index;
+        while slot < count && (intervals[slot].start as f64) < call_end {
+            let slot_start = intervals[slot].start as f64;
+            let overlap = call_end.min(slot_start + length as f64) - call_start.max(slot_start);
+            intervals[slot].carried += overlap / length as f64;
+            intervals[slot].offered += overlap / length as f64;
+            slot += 1;
+        }
+    }
+
+    Ok(intervals)
+}
+
+/// Finds the 60-minute window with the highest mean offered traffic. With 15-minute
+/// intervals the window slides in 15-minute steps.
+///
+/// # Arguments
+/// * `intervals` - Contiguous intervals as returned by `traffic_intervals`.
+///
+/// # Returns
+/// The busy hour, or `None` if the intervals cover less than one hour.
+pub fn busy_hour(intervals: &[TrafficInterval]) -> Option<BusyHour> {
+    let length = intervals.first()?.length;
+    let window = (3_600 / length).max(1) as usize;
+
+    intervals
+        .windows(window)
+        .map(|slots| BusyHour {
+            start: slots[0].start,
+            offered: slots.iter().map(|slot| slot.offered).sum::<f64>() / window as f64,
+            carried: slots.iter().map(|slot| slot.carried).sum::<f64>() / window as f64,
+        })
+        .fold(None, |best: Option<BusyHour>, hour| match best {
+            Some(best) if best.offered >= hour.offered => Some(best),
+            _ => Some(hour),
+        })
+}
+
+/// Number of days in a month of the proleptic Gregorian calendar.
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Parses a timestamp given as Unix seconds or `YYYY-MM-DD HH:MM[:SS]` (a `T` separator
+/// is also accepted) into Unix seconds.
+pub(crate) fn parse_timestamp(text: &str) -> Option<i64> {
+    if let Ok(seconds) = text.parse::<i64>() {
+        return Some(seconds);
+    }
+
+    let (date, time) = text.split_once([' ', 'T'])?;
+    let mut date_parts = date.splitn(3, '-');
+    let year: i64 = date_parts.next()?.parse().ok()?;
+    let month: u32 = date_parts.next()?.parse().ok()?;
+    let day: u32 = date_parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+
+    let time = time.trim_end_matches('Z');
+    let mut time_parts = time.splitn(3, ':');
+    let hour: i64 = time_parts.next()?.parse().ok()?;
+    let minute: i64 = time_parts.next()?.parse().ok()?;
+    let second: f64 = time_parts.next().map_or(Ok(0.0), str::parse).ok()?;
+    if !(0..24).contains(&hour) || !(0..60).contains(&minute) || !(0.0..61.0).contains(&second) {
+        return None;
+    }
+
+    Some(days_from_civil(year, month, day) * 86_400 + hour * 3_600 + minute * 60 + second as i64)
+}
+
+/// Parses a duration given as seconds or `[HH:]MM:SS`.
+fn parse_duration(text: &str) -> Option<f64> {
+    let seconds = text.split(':').try_fold(0.0, |total, part| {
+        let value = part.parse::<f64>().ok()?;
+        (value >= 0.0).then_some(total * 60.0 + value)
+    })?;
+
+    (seconds <= MAX_DURATION).then_some(seconds)
+}
+
+/// Number of days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month as i64 + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("1700000000"), Some(1_700_000_000));
+        assert_eq!(parse_timestamp("1970-01-01 00:00:00"), Some(0));
+        assert_eq!(parse_timestamp("2024-02-29T13:45"), Some(1_709_214_300));
+        assert_eq!(parse_timestamp("2024-13-01 00:00"), None);
+        // Days past the end of the month, with the Gregorian leap-year rule.
+        assert_eq!(parse_timestamp("2023-02-29 00:00"), None);
+        assert_eq!(parse_timestamp("2024-04-31 00:00"), None);
+        assert_eq!(parse_timestamp("1900-02-29 00:00"), None);
+        assert_eq!(parse_timestamp("2000-02-29 00:00"), Some(951_782_400));
+        assert_eq!(parse_duration("01:02:03"), Some(3_723.0));
+        assert_eq!(parse_duration("-5"), None);
+        assert_eq!(parse_duration("1:-30"), None);
+        assert_eq!(parse_duration("1e300"), None);
+        assert_eq!(parse_duration("NaN"), None);
+        assert_eq!(parse_duration("168:00:00"), Some(604_800.0));
+        assert_eq!(parse_duration("168:00:01"), None);
+    }
+
+    #[test]
+    fn test_parse_cdr_named_columns() {
+        let input = "caller;status;begin;secs\n\
+                     100;ANSWERED;2024-01-01 09:00:00;120\n\
+                     101;BUSY;2024-01-01 09:01:00;0\n";
+        let format = CdrFormat {
+            start: Column::Name("begin".to_string()),
+            duration: Column::Name("secs".to_string()),
+            status: Some(Column::Name("status".to_string())),
+            delimiter: ';',
+            ..CdrFormat::default()
+        };
+        let records = parse_cdr(input, &format).unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(records[0].answered);
+        assert!(!records[1].answered);
+
+        let missing = CdrFormat {
+            start: Column::Name("when".to_string()),
+            ..format.clone()
+        };
+        assert_eq!(
+            parse_cdr(input, &missing),
+            Err(CdrError::MissingColumn("when".to_string()))
+        );
+        assert!(matches!(
+            parse_cdr(
+                "start,duration,status\nsoon,10,answered\n",
+                &CdrFormat::default()
+            ),
+            Err(CdrError::InvalidRecord { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_traffic_intervals_and_busy_hour() {
+        // Ten overlapping 30-minute calls from 09:00, plus one failed attempt.
+        let mut input = String::from("start,duration,status\n");
+        for i in 0..10 {
+            input.push_str(&format!("2024-01-01 09:{:02}:00,1800,answered\n", i * 3));
+        }
+        input.push_str("2024-01-01 09:05:00,0,failed\n");
+        let records = parse_cdr(&input, &CdrFormat::default()).unwrap();
+
+        let intervals = traffic_intervals(&records, IntervalLength::Minutes15).unwrap();
+        assert_eq!(intervals[0].attempts, 6);
+        assert_eq!(intervals[0].answered, 5);
+        let carried: f64 = intervals.iter().map(|slot| slot.carried * 900.0).sum();
+        assert!((carried - 10.0 * 1_800.0).abs() < 1e-6);
+
+        let hour = busy_hour(&intervals).unwrap();
+        assert!((hour.carried - 5.0).abs() < 1e-9);
+        assert!((hour.offered - 5.5).abs() < 1e-9);
+        assert_eq!(hour.calculate_channels(0.01, 100), Ok(12));
+    }
+
+    #[test]
+    fn test_traffic_intervals_out_of_range() {
+        let call = |start: i64, duration: f64| CallRecord {
+            start,
+            duration,
+            answered: true,
+        };
+        assert_eq!(
+            traffic_intervals(&[], IntervalLength::Minutes15),
+            Ok(Vec::new())
+        );
+        assert_eq!(
+            traffic_intervals(&[call(i64::MAX - 10, 60.0)], IntervalLength::Minutes15),
+            Err(ErlangError::NumericOverflow)
+        );
+        assert!(matches!(
+            traffic_intervals(&[call(0, 1e300)], IntervalLength::Minutes15),
+            Err(ErlangError::InvalidParameter(_))
+        ));
+        // Unix seconds mixed with Unix milliseconds.
+        assert!(matches!(
+            traffic_intervals(
+                &[call(1_700_000_000, 60.0), call(1_700_000_000_000, 60.0)],
+                IntervalLength::Minutes60
+            ),
+            Err(ErlangError::InvalidParameter(_))
+        ));
+    }
+}
@@ -0,0 +1,273 @@
+// Erlang B for fractional channel counts via the incomplete gamma function.
+
+use crate::erlang_b;
+use crate::error::validate_traffic;
+use crate::stats::LANCZOS;
+
+/// Largest step used for the central differences in `erlang_b_derivative_channels`.
+const CHANNEL_STEP: f64 = 0.5;
+
+/// Gamma function for arguments in `[1, 2)`, accurate to about 1e-15.
+fn gamma(z: f64) -> f64 {
+    let z = z - 1.0;
+    let series = LANCZOS[1..]
+        .iter()
+        .enumerate()
+        .fold(LANCZOS[0], |sum, (i, c)| sum + c / (z + i as f64 + 1.0));
+    let t = z + 7.5;
+    (2.0 * std::f64::consts::PI).sqrt() * t.powf(z + 0.5) * (-t).exp() * series
+}
+
+/// `1/B(A, f)` for `0 < f < 1`, i.e. `e^A × A^(−f) × Γ(f + 1, A)`.
+fn base_inverse_blocking(traffic: f64, fraction: f64) -> f64 {
+    let a = fraction + 1.0;
+    if traffic > 3.0 {
+        // Continued fraction for Γ(a, A) × e^A × A^(−a) (modified Lentz).
+        let tiny = f64::MIN_POSITIVE / f64::EPSILON;
+        let mut b = traffic + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..1_000 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < f64::EPSILON {
+                break;
+            }
+        }
+        return traffic * h;
+    }
+
+    // Γ(a, A) = Γ(a) − γ(a, A), with γ(a, A) × e^A × A^(−a) = Σ A^n / (a (a + 1) … (a + n)).
+    let mut term = 1.0 / a;
+    let mut sum = term;
+    let mut n = 0.0;
+    while term > f64::EPSILON * sum {
+        n += 1.0;
+        term *= traffic / (a + n);
+        sum += term;
+    }
+    traffic.exp() * traffic.powf(-fraction) * gamma(a) - traffic * sum
+}
+
+/// Continuous Erlang B for valid, positive traffic and channels at or above zero.
+fn blocking(traffic: f64, channels: f64) -> f64 {
+    let whole = channels.floor();
+    let fraction = channels - whole;
+    if fraction == 0.0 && whole <= u32::MAX as f64 {
+        return erlang_b(traffic, whole as u32);
+    }
+
+    // The same scheme as `try_erlang_b`, offset by the fractional part: expand from the
+    // top of a window below the traffic, reaching down to `B(A, f)` only if needed.
+    let window = (10.0 * traffic.sqrt()).ceil();
+    let start = (traffic.floor() - window).clamp(0.0, whole);
+
+    let mut inverse_b = 1.0;
+    let mut term = 1.0;
+    let mut truncated = false;
+    let mut k = start;
+    while k >= 1.0 {
+        let n = fraction + k;
+        term *= n / traffic;
+        inverse_b += term;
+        if term * traffic < f64::EPSILON / 2.0 * inverse_b * (traffic - n) {
+            truncated = true;
+            break;
+        }
+        k -= 1.0;
+    }
+    if !truncated {
+        inverse_b += term * (base_inverse_blocking(traffic, fraction) - 1.0);
+    }
+
+    let mut blocking = 1.0 / inverse_b;
+    let mut k = start + 1.0;
+    while k <= whole {
+        let n = fraction + k;
+        blocking = traffic * blocking / (n + traffic * blocking);
+        if blocking == 0.0 {
+            break;
+        }
+        k += 1.0;
+    }
+
+    blocking
+}
+
+/// Continuous Erlang B extended below zero channels with `1/B(x) = (1/B(x + 1) − 1) × A / (x + 1)`,
+/// so central differences can straddle zero.
+fn blocking_extended(traffic: f64, channels: f64) -> f64 {
+    if channels >= 0.0 {
+        return blocking(traffic, channels);
+    }
+    let above = channels + 1.0;
+    1.0 / ((1.0 / blocking(traffic, above) - 1.0) * traffic / above)
+}
+
+/// Calculates the Erlang B blocking probability for a fractional number of channels.
+///
+/// The formula is extended to real channel counts `x` through the incomplete gamma
+/// function, `B(A, x) = A^x × e^(−A) / Γ(x + 1, A)`. At whole numbers the result is
+/// exactly `erlang_b`; in between it is evaluated with the same windowed recurrence,
+/// started from `Γ(f + 1, A)` for the fractional part `f` when the window reaches it.
+///
+/// # Arguments
+/// * `traffic` - The traffic load in Erlangs.
+/// * `channels` - The number of channels, possibly fractional (non-negative).
+///
+/// # Returns
+/// The blocking probability, or `NaN` for invalid traffic or channels.
+pub fn erlang_b_continuous(traffic: f64, channels: f64) -> f64 {
+    if validate_traffic(traffic).is_err() || !(channels >= 0.0 && channels.is_finite()) {
+        return f64::NAN;
+    }
+    if traffic == 0.0 {
+        return if channels == 0.0 { 1.0 } else { 0.0 };
+    }
+    blocking(traffic, channels)
+}
+
+/// Calculates the derivative of the continuous Erlang B formula with respect to traffic,
+/// `∂B/∂A = B × (x/A − 1 + B)`.
+///
+/// # Arguments
+/// * `traffic` - The traffic load in Erlangs (positive).
+/// * `channels` - The number of channels, possibly fractional (non-negative).
+///
+/// # Returns
+/// The derivative, or `NaN` for invalid input.
+pub fn erlang_b_derivative_traffic(traffic: f64, channels: f64) -> f64 {
+    if traffic <= 0.0 {
+        return f64::NAN;
+    }
+    let blocking = erlang_b_continuous(traffic, channels);
+    blocking * (channels / traffic - 1.0 + blocking)
+}
+
+/// Calculates the derivative of the continuous Erlang B formula with respect to the
+/// number of channels.
+///
+/// There is no closed form, so central differences at steps of 0.5, 0.25 and 0.125
+/// channels (shrunk by `1 + ln(x/A)` above the traffic, where the blocking falls
+/// steeply) are combined by Richardson extrapolation. The relative error is around 1e-8.
+///
+/// # Arguments
+/// * `traffic` - The traffic load in Erlangs (positive).
+/// * `channels` - The number of channels, possibly fractional (non-negative).
+///
+/// # Returns
+/// The derivative (negative), or `NaN` for invalid input.
+pub fn erlang_b_derivative_channels(traffic: f64, channels: f64) -> f64 {
+    if !(traffic > 0.0 && traffic.is_finite() && channels >= 0.0 && channels.is_finite()) {
+        return f64::NAN;
+    }
+
+    // Far above the traffic the blocking falls by a factor of about x/A per channel.
+    let step = CHANNEL_STEP / (1.0 + (channels / traffic).ln().max(0.0));
+    let central = |step: f64| {
+        (blocking_extended(traffic, channels + step) - blocking_extended(traffic, channels - step))
+            / (2.0 * step)
+    };
+    let coarse = central(step);
+    let medium = central(step / 2.0);
+    let fine = central(step / 4.0);
+
+    let first = (4.0 * medium - coarse) / 3.0;
+    let second = (4.0 * fine - medium) / 3.0;
+    (16.0 * second - first) / 15.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_integer_erlang_b() {
+        for (traffic, channels) in [(0.5, 3), (20.0, 30), (2_500.0, 2_550)] {
+            assert_eq!(
+                erlang_b_continuous(traffic, channels as f64),
+                erlang_b(traffic, channels)
+            );
+        }
+        let between = erlang_b_continuous(20.0, 25.5);
+        assert!(between < erlang_b(20.0, 25) && between > erlang_b(20.0, 26));
+        assert!(erlang_b_continuous(10.0, -0.5).is_nan());
+        assert_eq!(erlang_b_continuous(0.0, 2.5), 0.0);
+    }
+
+    #[test]
+    fn test_erlang_b_continuous() {
+        // (traffic, channels, B, ∂B/∂x, ∂B/∂A) from 40-digit incomplete gamma evaluations.
+        let references = [
+            (
+                1.0,
+                0.5,
+                0.725_196_777_358_348_6,
+                -0.501_166_813_554,
+                0.163_311_977_212,
+            ),
+            (
+                2.0,
+                1.5,
+                0.524_105_317_100_223_1,
+                -0.267_149_232_836,
+                0.143_660_054_138,
+            ),
+            (
+                10.0,
+                0.1,
+                0.990_849_840_359_798,
+                -0.091_439_468_831_4,
+                0.000_842_064_184_837,
+            ),
+            (
+                20.0,
+                25.5,
+                0.043_363_083_663_883_06,
+                -0.013_018_012_681_7,
+                0.013_805_205_032_4,
+            ),
+            (
+                200.0,
+                210.25,
+                0.027_306_654_245_401_85,
+                -0.002_155_680_876_36,
+                0.002_145_119_396_15,
+            ),
+            (
+                5_000.0,
+                5_100.75,
+                0.002_206_570_968_176_162,
+                -4.905_689_927_17e-5,
+                4.933_136_044_63e-5,
+            ),
+            (
+                1.0,
+                40.5,
+                7.063_054_499_007_76e-50,
+                -2.622_933_694_57e-49,
+                2.789_906_527_11e-48,
+            ),
+        ];
+        for (traffic, channels, expected, d_channels, d_traffic) in references {
+            let blocking = erlang_b_continuous(traffic, channels);
+            assert!(((blocking - expected) / expected).abs() < 1e-13);
+            let derivative = erlang_b_derivative_channels(traffic, channels);
+            assert!(((derivative - d_channels) / d_channels).abs() < 1e-7);
+            let derivative = erlang_b_derivative_traffic(traffic, channels);
+            assert!(((derivative - d_traffic) / d_traffic).abs() < 1e-10);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+// Minimal CSV field splitting and joining shared by the file-based inputs and outputs.
+
+/// Splits one CSV line into trimmed fields. Fields may be wrapped in double quotes,
+/// in which case the delimiter is ignored inside them and `""` stands for a quote.
+pub(crate) fn split_line(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                field.push(c);
+            }
+        } else if c == '"' {
+            in_quotes = true;
+        } else if c == delimiter {
+            fields.push(field.trim().to_string());
+            field.clear();
+        } else {
+            field.push(c);
+        }
+    }
+
+    fields.push(field.trim().to_string());
+    fields
+}
+
+/// Joins fields into one CSV line, quoting any field that contains the delimiter, a quote
+/// or a line break.
+pub(crate) fn join_fields(fields: &[String], delimiter: char) -> String {
+    let quoted: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([delimiter, '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect();
+    quoted.join(&delimiter.to_string())
+}
+
+/// Returns the non-empty, non-comment lines of a CSV document with their 1-based line numbers.
+pub(crate) fn data_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim_end_matches('\r')))
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_line() {
+        assert_eq!(split_line("a, b ,c", ','), vec!["a", "b", "c"]);
+        assert_eq!(
+            split_line("\"x;y\";\"say \"\"hi\"\"\"", ';'),
+            vec!["x;y", "say \"hi\""]
+        );
+        assert_eq!(split_line("", ','), vec![""]);
+    }
+
+    #[test]
+    fn test_join_fields() {
+        let fields = vec!["a".to_string(), "b,c".to_string(), "say \"hi\"".to_string()];
+        let line = join_fields(&fields, ',');
+        assert_eq!(line, "a,\"b,c\",\"say \"\"hi\"\"\"");
+        assert_eq!(split_line(&line, ','), fields);
+    }
+
+    #[test]
+    fn test_data_lines() {
+        let lines: Vec<_> = data_lines("# comment\na,b\r\n\n c,d").collect();
+        assert_eq!(lines, vec![(2, "a,b"), (4, " c,d")]);
+    }
+}
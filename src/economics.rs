@@ -0,0 +1,333 @@
+// Economic trunk group sizing: capacity cost against revenue lost to blocking.
+
+use crate::error::validate_traffic;
+use crate::trunk::TrunkType;
+use crate::{erlang_b, ErlangError};
+
+/// Cost of providing capacity over the costing period.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CapacityCost {
+    /// Cost per span; candidate sizes are whole spans of the given trunk type.
+    PerSpan {
+        /// Cost of one span over the period.
+        cost: f64,
+        /// The trunk type providing the spans.
+        trunk_type: TrunkType,
+    },
+    /// Cost per channel; candidate sizes are channel counts.
+    PerChannel(f64),
+}
+
+/// Revenue lost to blocking over the costing period.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LostRevenue {
+    /// Value of each blocked call, with the number of calls offered over the period.
+    PerBlockedCall {
+        /// Revenue lost with each blocked call.
+        value: f64,
+        /// Number of calls offered over the period.
+        offered_calls: f64,
+    },
+    /// Penalty per Erlang of lost traffic.
+    PerErlangLost(f64),
+}
+
+impl LostRevenue {
+    /// Converts the revenue to a value per lost Erlang: blocked calls are
+    /// `offered_calls × B` while lost traffic is `A × B`.
+    fn per_erlang(self, traffic: f64) -> f64 {
+        match self {
+            LostRevenue::PerBlockedCall {
+                value,
+                offered_calls,
+            } if traffic > 0.0 => value * offered_calls / traffic,
+            LostRevenue::PerBlockedCall { .. } => 0.0,
+            LostRevenue::PerErlangLost(penalty) => penalty,
+        }
+    }
+}
+
+/// Costs of one candidate trunk group size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostPoint {
+    /// Number of spans, when capacity is costed per span.
+    pub spans: Option<u32>,
+    /// Number of bearer channels.
+    pub channels: u32,
+    /// Erlang B blocking probability.
+    pub blocking: f64,
+    /// Lost traffic `A × B` in Erlangs.
+    pub lost_traffic: f64,
+    /// Cost of the capacity.
+    pub capacity_cost: f64,
+    /// Revenue lost to blocking.
+    pub lost_revenue: f64,
+    /// Capacity cost plus lost revenue.
+    pub total_cost: f64,
+    /// Lost revenue recovered by the last increment (channel or span) over the next
+    /// smaller size; zero for the smallest size.
+    pub marginal_value: f64,
+    /// Cost of the last increment; zero for the smallest size.
+    pub marginal_cost: f64,
+}
+
+/// Total cost across candidate sizes, with the cheapest one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostCurve {
+    /// One point per candidate size, smallest first.
+    pub points: Vec<CostPoint>,
+    /// Index of the point with the lowest total cost.
+    pub optimal_index: usize,
+}
+
+impl CostCurve {
+    /// The point with the lowest total cost.
+    pub fn optimal(&self) -> &CostPoint {
+        &self.points[self.optimal_index]
+    }
+}
+
+fn validate_amount(amount: f64, message: &'static str) -> Result<f64, ErlangError> {
+    if amount >= 0.0 && amount.is_finite() {
+        Ok(amount)
+    } else {
+        Err(ErlangError::InvalidParameter(message))
+    }
+}
+
+/// Largest `size_max` accepted by `cost_curve`, which keeps one point per candidate size.
+/// Its channels (fewer than 32 per span) stay well within `u32`.
+const MAX_CURVE_SIZE: u32 = 1_000_000;
+
+/// Calculates the total cost of every candidate size from zero up to `size_max`, and the
+/// size that minimises it.
+///
+/// Each point carries the marginal value of its last increment, `v × A × (B(N−k) − B(N))`
+/// for a value `v` per lost Erlang, next to its marginal cost. The lost traffic is convex
+/// in the channel count, so the cheapest size is where the marginal value last exceeds
+/// the marginal cost (Moe's principle).
+///
+/// # Arguments
+/// * `traffic` - Offered traffic in Erlangs.
+/// * `capacity` - Cost per span or per channel.
+/// * `lost` - Revenue lost per blocked call or per lost Erlang.
+/// * `size_max` - Largest candidate size, in spans or channels depending on `capacity`
+///   (at most 1,000,000).
+///
+/// # Returns
+/// The cost curve, `ErlangError::InvalidParameter` for a negative or non-finite amount or
+/// a `size_max` above the limit, or an error for invalid traffic.
+pub fn cost_curve(
+    traffic: f64,
+    capacity: CapacityCost,
+    lost: LostRevenue,
+    size_max: u32,
+) -> Result<CostCurve, ErlangError> {
+    validate_traffic(traffic)?;
+    let (unit_cost, channels_per_unit, per_span) = match capacity {
+        CapacityCost::PerSpan { cost, trunk_type } => (cost, trunk_type.bearer_channels(), true),
+        CapacityCost::PerChannel(cost) => (cost, 1, false),
+    };
+    validate_amount(unit_cost, "capacity cost must be finite and non-negative")?;
+    match lost {
+        LostRevenue::PerBlockedCall {
+            value,
+            offered_calls,
+        } => {
+            validate_amount(value, "blocked call value must be finite and non-negative")?;
+            validate_amount(
+                offered_calls,
+                "offered calls must be finite and non-negative",
+            )?;
+        }
+        LostRevenue::PerErlangLost(penalty) => {
+            validate_amount(
+                penalty,
+                "lost Erlang penalty must be finite and non-negative",
+            )?;
+        }
+    }
+    if size_max > MAX_CURVE_SIZE {
+        return Err(ErlangError::InvalidParameter(
+            "cost curves are limited to 1,000,000 candidate sizes",
+        ));
+    }
+    let value_per_erlang = lost.per_erlang(traffic);
+
+    let mut points: Vec<CostPoint> = Vec::new();
+    for size in 0..=size_max {
+        let channels = size * channels_per_unit;
+        let blocking = erlang_b(traffic, channels);
+        let lost_traffic = traffic * blocking;
+        let capacity_cost = size as f64 * unit_cost;
+        let lost_revenue = lost_traffic * value_per_erlang;
+        let (marginal_value, marginal_cost) = match points.last() {
+            Some(previous) => (previous.lost_revenue - lost_revenue, unit_cost),
+            None => (0.0, 0.0),
+        };
+        points.push(CostPoint {
+            spans: per_span.then_some(size),
+            channels,
+            blocking,
+            lost_traffic,
+            capacity_cost,
+            lost_revenue,
+            total_cost: capacity_cost + lost_revenue,
+            marginal_value,
+            marginal_cost,
+        });
+    }
+
+    let optimal_index = points.iter().enumerate().fold(0, |best, (index, point)| {
+        if point.total_cost < points[best].total_cost {
+            index
+        } else {
+            best
+        }
+    });
+
+    Ok(CostCurve {
+        points,
+        optimal_index,
+    })
+}
+
+/// Calculates the value of the `channels`-th channel of a sequentially hunted group:
+/// the traffic it carries, `A × (B(N−1) − B(N))`, times the value of a carried Erlang.
+///
+/// # Arguments
+/// * `traffic` - Offered traffic in Erlangs.
+/// * `channels` - Position of the channel (at least 1).
+/// * `value_per_erlang` - Revenue per Erlang carried.
+///
+/// # Returns
+/// The marginal value, or `NaN` for invalid traffic.
+pub fn marginal_channel_value(traffic: f64, channels: u32, value_per_erlang: f64) -> f64 {
+    if channels == 0 {
+        return 0.0;
+    }
+    value_per_erlang * traffic * (erlang_b(traffic, channels - 1) - erlang_b(traffic, channels))
+}
+
+/// Sizes a trunk group with Moe's principle: channels are added while the next channel
+/// earns more than it costs.
+///
+/// # Arguments
+/// * `traffic` - Offered traffic in Erlangs.
+/// * `value_per_erlang` - Revenue per Erlang carried (or lost).
+/// * `cost_per_channel` - Cost of one channel over the same period (positive, since free
+///   channels would always pay for themselves).
+/// * `channels_max` - Maximum number of channels to search for.
+///
+/// # Returns
+/// The number of channels, `ErlangError::InvalidParameter` for a cost that is not
+/// positive, `ErlangError::SearchLimitExceeded` if the next channel still pays for itself
+/// at `channels_max`, or an input error.
+pub fn moe_channels(
+    traffic: f64,
+    value_per_erlang: f64,
+    cost_per_channel: f64,
+    channels_max: u32,
+) -> Result<u32, ErlangError> {
+    validate_traffic(traffic)?;
+    validate_amount(
+        value_per_erlang,
+        "value per Erlang must be finite and non-negative",
+    )?;
+    if !(cost_per_channel > 0.0 && cost_per_channel.is_finite()) {
+        return Err(ErlangError::InvalidParameter(
+            "channel cost must be finite and positive",
+        ));
+    }
+
+    for channels in 0..channels_max {
+        if marginal_channel_value(traffic, channels + 1, value_per_erlang) < cost_per_channel {
+            return Ok(channels);
+        }
+    }
+
+    Err(ErlangError::SearchLimitExceeded {
+        channels_max,
+        best_blocking: erlang_b(traffic, channels_max),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cost_curve_per_channel() {
+        let curve = cost_curve(
+            50.0,
+            CapacityCost::PerChannel(1.0),
+            LostRevenue::PerErlangLost(30.0),
+            120,
+        )
+        .unwrap();
+        assert_eq!(curve.points.len(), 121);
+        let optimal = curve.optimal();
+        assert!(curve
+            .points
+            .iter()
+            .all(|point| point.total_cost >= optimal.total_cost));
+
+        // Moe's principle: the last channel pays for itself, the next one would not.
+        assert!(optimal.marginal_value >= optimal.marginal_cost);
+        let next = curve.points[curve.optimal_index + 1];
+        assert!(next.marginal_value < next.marginal_cost);
+        assert_eq!(moe_channels(50.0, 30.0, 1.0, 1_000), Ok(optimal.channels));
+        assert!(matches!(
+            moe_channels(50.0, 30.0, 0.0, 1_000),
+            Err(ErlangError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_cost_curve_per_span() {
+        let per_span = CapacityCost::PerSpan {
+            cost: 30.0,
+            trunk_type: TrunkType::E1Pri,
+        };
+        // 5,000 calls of 3 minutes in the period are 250 Erlang-hours: 50 Erlangs over 5 hours.
+        let per_call = LostRevenue::PerBlockedCall {
+            value: 0.5,
+            offered_calls: 5_000.0,
+        };
+        let curve = cost_curve(50.0, per_span, per_call, 6).unwrap();
+        let optimal = curve.optimal();
+        assert_eq!(optimal.spans, Some(optimal.channels / 30));
+        assert_eq!(curve.points[0].lost_revenue, 2_500.0);
+
+        let per_erlang = cost_curve(50.0, per_span, LostRevenue::PerErlangLost(50.0), 6).unwrap();
+        assert_eq!(per_erlang.optimal_index, curve.optimal_index);
+    }
+
+    #[test]
+    fn test_marginal_channel_value() {
+        // The first channel carries A / (1 + A) Erlangs.
+        assert!((marginal_channel_value(4.0, 1, 10.0) - 8.0).abs() < 1e-12);
+        let values: Vec<f64> = (1..40)
+            .map(|n| marginal_channel_value(20.0, n, 1.0))
+            .collect();
+        assert!(values.windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(matches!(
+            cost_curve(
+                10.0,
+                CapacityCost::PerChannel(-1.0),
+                LostRevenue::PerErlangLost(1.0),
+                10
+            ),
+            Err(ErlangError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            cost_curve(
+                10.0,
+                CapacityCost::PerChannel(1.0),
+                LostRevenue::PerErlangLost(1.0),
+                u32::MAX
+            ),
+            Err(ErlangError::InvalidParameter(_))
+        ));
+    }
+}
@@ -0,0 +1,254 @@
+// Engset loss model for finite subscriber populations.
+
+use crate::error::validate_blocking_probability;
+use crate::ErlangError;
+
+/// Calculates the Engset time congestion, i.e. the fraction of time all channels are busy.
+/// This iterative approach mirrors the Erlang B recurrence, weighting each step
+/// by the number of sources that are still idle.
+///
+/// # Arguments
+/// * `sources` - The number of traffic sources (subscribers).
+/// * `idle_source_traffic` - Offered traffic per idle source in Erlangs (call rate × holding time).
+/// * `channels` - The number of communication channels.
+///
+/// # Returns
+/// The time congestion. Returns `0.0` when there are more channels than sources.
+pub fn engset_time_congestion(sources: u32, idle_source_traffic: f64, channels: u32) -> f64 {
+    let mut congestion = 1.0;
+
+    for n in 1..=channels {
+        if n > sources {
+            return 0.0;
+        }
+        let offered = (sources - n + 1) as f64 * idle_source_traffic * congestion;
+        congestion = offered / (n as f64 + offered);
+    }
+
+    congestion
+}
+
+/// Calculates the Engset call congestion, i.e. the probability that a call attempt is blocked.
+/// An arriving call only sees the other `sources - 1` subscribers, so the call
+/// congestion equals the time congestion of a population one smaller.
+///
+/// # Arguments
+/// * `sources` - The number of traffic sources (subscribers).
+/// * `idle_source_traffic` - Offered traffic per idle source in Erlangs.
+/// * `channels` - The number of communication channels.
+///
+/// # Returns
+/// The call congestion (blocking probability seen by call attempts).
+pub fn engset_call_congestion(sources: u32, idle_source_traffic: f64, channels: u32) -> f64 {
+    if sources == 0 {
+        return 0.0;
+    }
+    engset_time_congestion(sources - 1, idle_source_traffic, channels)
+}
+
+/// Converts the offered traffic of a single subscriber into the offered traffic
+/// per idle source used by the Engset formulas.
+///
+/// # Arguments
+/// * `source_traffic` - Offered traffic per subscriber in Erlangs (must be below 1).
+///
+/// # Returns
+/// The offered traffic per idle source, or `None` if a subscriber offers 1 Erlang or more.
+pub fn idle_source_traffic(source_traffic: f64) -> Option<f64> {
+    if !(0.0..1.0).contains(&source_traffic) {
+        return None;
+    }
+    Some(source_traffic / (1.0 - source_traffic))
+}
+
+/// Iteratively calculates the number of channels required to keep the Engset call
+/// congestion at or below a given blocking probability.
+///
+/// # Arguments
+/// * `sources` - The number of traffic sources (subscribers).
+/// * `idle_source_traffic` - Offered traffic per idle source in Erlangs.
+/// * `blocking_probability` - Desired blocking probability (between 0 and 1).
+///
+/// # Returns
+/// Returns the number of channels required to meet the blocking probability. The search
+/// never exceeds `sources` channels, at which point no call can be blocked. Inputs are not
+/// checked; use `try_calculate_engset_channels` to reject them.
+pub fn calculate_engset_channels(
+    sources: u32,
+    idle_source_traffic: f64,
+    blocking_probability: f64,
+) -> u32 {
+    let mut channels = 1;
+
+    while channels < sources {
+        let blocking = engset_call_congestion(sources, idle_source_traffic, channels);
+        if blocking <= blocking_probability {
+            return channels;
+        }
+        channels += 1;
+    }
+
+    sources
+}
+
+/// Iteratively calculates the number of channels required to keep the Engset call
+/// congestion at or below a given blocking probability, validating the inputs first.
+///
+/// # Arguments
+/// * `sources` - The number of traffic sources (subscribers).
+/// * `idle_source_traffic` - Offered traffic per idle source in Erlangs.
+/// * `blocking_probability` - Desired blocking probability (between 0 and 1).
+///
+/// # Returns
+/// The number of channels required to meet the blocking probability, or an error:
+/// * `ErlangError::InvalidBlockingProbability` if `blocking_probability` is not strictly
+///   between 0 and 1.
+/// * `ErlangError::InvalidParameter` if `idle_source_traffic` is negative or not finite.
+pub fn try_calculate_engset_channels(
+    sources: u32,
+    idle_source_traffic: f64,
+    blocking_probability: f64,
+) -> Result<u32, ErlangError> {
+    let blocking_probability = validate_blocking_probability(blocking_probability)?;
+    if !(idle_source_traffic >= 0.0 && idle_source_traffic.is_finite()) {
+        return Err(ErlangError::InvalidParameter(
+            "idle source traffic must be finite and non-negative",
+        ));
+    }
+    Ok(calculate_engset_channels(
+        sources,
+        idle_source_traffic,
+        blocking_probability,
+    ))
+}
+
+/// Calculates the required number of voice channels for a finite group of users using
+/// the Engset model, with the same inputs as `required_e1_channels`.
+///
+/// Unlike `required_e1_channels`, the number of users is kept as the size of the source
+/// population instead of being collapsed into an infinite-source traffic figure.
+///
+/// This is a thin wrapper around `try_required_engset_channels` that maps every error
+/// to `None`.
+///
+/// # Arguments
+/// * `users` - Number of users.
+/// * `average_call_duration` - Average call duration in minutes.
+/// * `concurrent_calls` - Calls per user in the busy hour.
+/// * `blocking_probability` - Desired blocking probability.
+///
+/// # Returns
+/// Number of required voice channels, or `None` if an input is invalid or each user
+/// offers 1 Erlang or more.
+pub fn required_engset_channels(
+    users: u32,
+    average_call_duration: f64,
+    concurrent_calls: u32,
+    blocking_probability: f64,
+) -> Option<u32> {
+    try_required_engset_channels(
+        users,
+        average_call_duration,
+        concurrent_calls,
+        blocking_probability,
+    )
+    .ok()
+}
+
+/// Calculates the required number of voice channels for a finite group of users using
+/// the Engset model, reporting invalid inputs.
+///
+/// # Arguments
+/// * `users` - Number of users.
+/// * `average_call_duration` - Average call duration in minutes.
+/// * `concurrent_calls` - Calls per user in the busy hour.
+/// * `blocking_probability` - Desired blocking probability.
+///
+/// # Returns
+/// Number of required voice channels, or an error:
+/// * `ErlangError::InvalidBlockingProbability` if `blocking_probability` is not strictly
+///   between 0 and 1.
+/// * `ErlangError::InvalidParameter` if the call duration is negative or not finite, or
+///   each user offers 1 Erlang or more.
+pub fn try_required_engset_channels(
+    users: u32,
+    average_call_duration: f64,
+    concurrent_calls: u32,
+    blocking_probability: f64,
+) -> Result<u32, ErlangError> {
+    let blocking_probability = validate_blocking_probability(blocking_probability)?;
+    if !(average_call_duration >= 0.0 && average_call_duration.is_finite()) {
+        return Err(ErlangError::InvalidParameter(
+            "call duration must be finite and non-negative",
+        ));
+    }
+
+    let source_traffic = (average_call_duration * concurrent_calls as f64) / 60.0;
+    let beta = idle_source_traffic(source_traffic).ok_or(ErlangError::InvalidParameter(
+        "each user must offer less than 1 Erlang",
+    ))?;
+    try_calculate_engset_channels(users, beta, blocking_probability)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{calculate_e1_channels, erlang_b};
+
+    #[test]
+    fn test_engset_congestion() {
+        // 10 sources, beta = 0.2, 4 channels: E = C(10,4)·0.2^4 / sum C(10,i)·0.2^i.
+        let time = engset_time_congestion(10, 0.2, 4);
+        assert!((time - 0.055_118).abs() < 1e-5);
+        let call = engset_call_congestion(10, 0.2, 4);
+        assert!(call < time);
+        assert_eq!(engset_time_congestion(3, 0.5, 4), 0.0);
+    }
+
+    #[test]
+    fn test_engset_approaches_erlang_b() {
+        // A large population with the same total traffic behaves like Erlang B.
+        let sources = 100_000;
+        let beta = idle_source_traffic(10.0 / sources as f64).unwrap();
+        let engset = engset_call_congestion(sources, beta, 15);
+        assert!((engset - erlang_b(10.0, 15)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_required_engset_channels() {
+        // 40 extensions at 0.15 Erlang each: Engset needs fewer channels than Erlang B.
+        let engset = required_engset_channels(40, 3.0, 3, 0.01).unwrap();
+        let erlang = calculate_e1_channels(40.0 * 0.15, 0.01, 1_000).unwrap();
+        assert!(engset < erlang);
+        assert!(engset_call_congestion(40, idle_source_traffic(0.15).unwrap(), engset) <= 0.01);
+        assert_eq!(required_engset_channels(40, 30.0, 3, 0.01), None);
+    }
+
+    #[test]
+    fn test_try_required_engset_channels() {
+        assert_eq!(
+            try_required_engset_channels(40, 3.0, 3, 0.01).ok(),
+            required_engset_channels(40, 3.0, 3, 0.01)
+        );
+        assert_eq!(
+            try_required_engset_channels(40, 3.0, 3, 0.0),
+            Err(ErlangError::InvalidBlockingProbability(0.0))
+        );
+        for duration in [-1.0, f64::NAN, f64::INFINITY, 30.0] {
+            assert!(matches!(
+                try_required_engset_channels(40, duration, 3, 0.01),
+                Err(ErlangError::InvalidParameter(_))
+            ));
+        }
+        assert_eq!(
+            try_calculate_engset_channels(40, 0.2, 1.0),
+            Err(ErlangError::InvalidBlockingProbability(1.0))
+        );
+        for beta in [-0.1, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                try_calculate_engset_channels(40, beta, 0.01),
+                Err(ErlangError::InvalidParameter(_))
+            ));
+        }
+    }
+}
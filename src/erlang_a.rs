@@ -0,0 +1,294 @@
+// Erlang A (M/M/n+M) calculations: queued calls where callers abandon while waiting.
+
+use crate::{erlang_b, ErlangError};
+
+/// Performance of an Erlang A queue.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ErlangAMetrics {
+    /// Offered traffic in Erlangs (arrival rate × mean handle time).
+    pub traffic: f64,
+    /// Probability that an arriving call has to wait.
+    pub probability_of_waiting: f64,
+    /// Probability that an arriving call hangs up before it is answered.
+    pub probability_of_abandonment: f64,
+    /// Mean number of calls waiting.
+    pub average_queue_length: f64,
+    /// Average waiting time over all offered calls, answered or abandoned, in seconds.
+    pub average_speed_of_answer: f64,
+    /// Fraction of offered calls answered within the target answer time.
+    pub service_level: f64,
+}
+
+fn validate_positive(value: f64, message: &'static str) -> Result<f64, ErlangError> {
+    if value > 0.0 && !value.is_nan() {
+        Ok(value)
+    } else {
+        Err(ErlangError::InvalidParameter(message))
+    }
+}
+
+fn validate_non_negative(value: f64, message: &'static str) -> Result<f64, ErlangError> {
+    if value >= 0.0 && value.is_finite() {
+        Ok(value)
+    } else {
+        Err(ErlangError::InvalidParameter(message))
+    }
+}
+
+/// Calculates the performance of an M/M/n+M queue: Poisson arrivals, exponential handle
+/// times and exponential patience.
+///
+/// With `k` calls waiting, the queue shrinks at rate `nμ + kθ` (a service completion or
+/// an abandonment), so the states above `n` are `π(n + k) = π(n) × Π λ / (nμ + iθ)` and
+/// the states below `n` follow Erlang B. The abandonment rate is `θ × E[queue]`. The
+/// service level is found by uniformising the position of a waiting call in the queue.
+/// An infinite patience gives Erlang C.
+///
+/// # Arguments
+/// * `arrival_rate` - Calls arriving per second.
+/// * `average_handle_time` - Average handle time of a call in seconds.
+/// * `average_patience` - Average time a caller waits before hanging up, in seconds;
+///   `f64::INFINITY` for callers who never abandon.
+/// * `agents` - The number of agents answering calls.
+/// * `target_answer_time` - Answer time threshold in seconds for the service level.
+///
+/// # Returns
+/// The metrics, or `ErlangError::InvalidParameter` for a negative or non-finite input.
+/// Without arrivals nobody waits and the service level is `1.0`.
+/// Without abandonment an overloaded queue (`agents <= traffic`) has every call waiting,
+/// an infinite ASA and a service level of `0.0`, as in Erlang C.
+pub fn erlang_a(
+    arrival_rate: f64,
+    average_handle_time: f64,
+    average_patience: f64,
+    agents: u32,
+    target_answer_time: f64,
+) -> Result<ErlangAMetrics, ErlangError> {
+    validate_non_negative(arrival_rate, "arrival rate must be finite and non-negative")?;
+    validate_positive(average_handle_time, "handle time must be positive")?;
+    if average_handle_time.is_infinite() {
+        return Err(ErlangError::InvalidParameter("handle time must be finite"));
+    }
+    validate_positive(average_patience, "patience must be positive")?;
+    validate_non_negative(
+        target_answer_time,
+        "target answer time must be finite and non-negative",
+    )?;
+
+    let traffic = arrival_rate * average_handle_time;
+    let abandonment_rate = 1.0 / average_patience;
+    let service_rate = agents as f64 / average_handle_time;
+
+    if arrival_rate == 0.0 {
+        return Ok(ErlangAMetrics {
+            traffic,
+            probability_of_waiting: 0.0,
+            probability_of_abandonment: 0.0,
+            average_queue_length: 0.0,
+            average_speed_of_answer: 0.0,
+            service_level: 1.0,
+        });
+    }
+    if abandonment_rate == 0.0 && service_rate <= arrival_rate {
+        return Ok(ErlangAMetrics {
+            traffic,
+            probability_of_waiting: 1.0,
+            probability_of_abandonment: 0.0,
+            average_queue_length: f64::INFINITY,
+            average_speed_of_answer: f64::INFINITY,
+            service_level: 0.0,
+        });
+    }
+
+    // Queue states relative to π(n), until the terms no longer affect the sums.
+    let mut queue = vec![1.0];
+    let mut queue_sum = 1.0;
+    let mut queue_moment = 0.0;
+    let mut term = 1.0;
+    let mut waiting = 0.0;
+    loop {
+        waiting += 1.0;
+        let departure_rate = service_rate + waiting * abandonment_rate;
+        term *= arrival_rate / departure_rate;
+        queue.push(term);
+        queue_sum += term;
+        queue_moment += waiting * term;
+        if departure_rate > arrival_rate && waiting * term < f64::EPSILON * queue_moment {
+            break;
+        }
+        if queue.len() > 10_000_000 || !queue_sum.is_finite() {
+            return Err(ErlangError::NumericOverflow);
+        }
+    }
+
+    let blocking = erlang_b(traffic, agents);
+    let at_agents = 1.0 / (1.0 / blocking - 1.0 + queue_sum);
+    let probability_of_waiting = at_agents * queue_sum;
+    let average_queue_length = at_agents * queue_moment;
+    let probability_of_abandonment = abandonment_rate * average_queue_length / arrival_rate;
+    let average_speed_of_answer = average_queue_length / arrival_rate;
+
+    let answered_in_queue =
+        answered_within(&queue, service_rate, abandonment_rate, target_answer_time);
+
+    Ok(ErlangAMetrics {
+        traffic,
+        probability_of_waiting,
+        probability_of_abandonment,
+        average_queue_length,
+        average_speed_of_answer,
+        service_level: 1.0 - probability_of_waiting + at_agents * answered_in_queue,
+    })
+}
+
+/// Sums, over the queue states relative to π(n), the probability that a call arriving
+/// with `k` calls ahead of it is answered within `time`.
+///
+/// Ahead of the call, `k` waiting calls leave at `nμ + kθ`; the call itself abandons at
+/// `θ`. The chain is uniformised at its largest total rate and the answered mass after
+/// each jump is weighted by the Poisson probability of that many jumps, up to ten
+/// standard deviations past the mean.
+fn answered_within(queue: &[f64], service_rate: f64, abandonment_rate: f64, time: f64) -> f64 {
+    let uniform_rate = service_rate + queue.len() as f64 * abandonment_rate;
+    let mean_jumps = uniform_rate * time;
+    if mean_jumps == 0.0 {
+        return 0.0;
+    }
+
+    let jumps_max = (mean_jumps + 10.0 * mean_jumps.sqrt() + 20.0).ceil() as usize;
+    let mut mass = queue.to_vec();
+    let mut answered = 0.0;
+    let mut result = 0.0;
+    let mut log_factorial = 0.0;
+    for jumps in 1..=jumps_max {
+        answered += mass[0] * service_rate / uniform_rate;
+        for ahead in 0..mass.len() {
+            let leave = service_rate + ahead as f64 * abandonment_rate;
+            let stay = 1.0 - (leave + abandonment_rate) / uniform_rate;
+            let advance = match mass.get(ahead + 1) {
+                Some(&behind) => behind * (leave + abandonment_rate) / uniform_rate,
+                None => 0.0,
+            };
+            mass[ahead] = mass[ahead] * stay + advance;
+        }
+        log_factorial += (jumps as f64).ln();
+        let weight = (jumps as f64 * mean_jumps.ln() - mean_jumps - log_factorial).exp();
+        result += weight * answered;
+    }
+
+    result
+}
+
+/// Iteratively calculates the minimum number of agents required to reach a target
+/// service level with the Erlang A model.
+///
+/// # Arguments
+/// * `arrival_rate` - Calls arriving per second.
+/// * `average_handle_time` - Average handle time of a call in seconds.
+/// * `average_patience` - Average time a caller waits before hanging up, in seconds.
+/// * `target_answer_time` - Answer time threshold in seconds.
+/// * `target_service_level` - Desired fraction of calls answered within the threshold (between 0 and 1).
+/// * `agents_max` - Maximum number of agents to search for.
+///
+/// # Returns
+/// Returns the number of agents required to meet the service level, or `None`
+/// if the number of agents exceeds `agents_max` or an input is invalid.
+pub fn calculate_agents(
+    arrival_rate: f64,
+    average_handle_time: f64,
+    average_patience: f64,
+    target_answer_time: f64,
+    target_service_level: f64,
+    agents_max: u32,
+) -> Option<u32> {
+    // At most n/A of the calls can be answered at all, so fewer agents cannot qualify.
+    let traffic = arrival_rate * average_handle_time;
+    let mut agents = ((traffic * target_service_level).ceil() as u32).max(1);
+
+    while agents < agents_max {
+        let metrics = erlang_a(
+            arrival_rate,
+            average_handle_time,
+            average_patience,
+            agents,
+            target_answer_time,
+        )
+        .ok()?;
+        if metrics.service_level >= target_service_level {
+            return Some(agents);
+        }
+        agents += 1;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::erlang_c;
+
+    #[test]
+    fn test_infinite_patience_is_erlang_c() {
+        // 10 Erlangs: 1 call every 18 seconds with a 180 second handle time.
+        let metrics = erlang_a(1.0 / 18.0, 180.0, f64::INFINITY, 12, 20.0).unwrap();
+        assert!((metrics.traffic - 10.0).abs() < 1e-12);
+        assert!((metrics.probability_of_waiting - erlang_c::erlang_c(10.0, 12)).abs() < 1e-12);
+        assert_eq!(metrics.probability_of_abandonment, 0.0);
+        let asa = erlang_c::average_speed_of_answer(10.0, 12, 180.0);
+        assert!((metrics.average_speed_of_answer - asa).abs() < 1e-9 * asa);
+        let level = erlang_c::service_level(10.0, 12, 180.0, 20.0);
+        assert!((metrics.service_level - level).abs() < 1e-9);
+
+        let overloaded = erlang_a(1.0 / 18.0, 180.0, f64::INFINITY, 10, 20.0).unwrap();
+        assert_eq!(overloaded.probability_of_waiting, 1.0);
+        assert_eq!(overloaded.service_level, 0.0);
+    }
+
+    #[test]
+    fn test_patience_equal_to_handle_time() {
+        // With θ = μ every call leaves at rate μ, so the number in the system is Poisson(A)
+        // as in M/M/∞: P(wait) = P(N ≥ n) and P(abandon) = E[(N − n)⁺] / A.
+        let metrics = erlang_a(0.05, 180.0, 180.0, 8, 20.0).unwrap();
+        assert!((metrics.probability_of_waiting - 0.676103035687104).abs() < 1e-12);
+        assert!((metrics.probability_of_abandonment - 0.1922386839736984).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_service_level() {
+        // Reference from the hypoexponential time to answer, summed over queue positions.
+        let metrics = erlang_a(0.05, 180.0, 120.0, 10, 20.0).unwrap();
+        assert!((metrics.service_level - 0.7393541473).abs() < 1e-9);
+        assert!(metrics.probability_of_abandonment > 0.0);
+        assert!(
+            metrics.probability_of_waiting < erlang_c::erlang_c(9.0, 10),
+            "abandonment shortens the queue"
+        );
+        let immediate = erlang_a(0.05, 180.0, 120.0, 10, 0.0).unwrap();
+        assert!((immediate.service_level - (1.0 - immediate.probability_of_waiting)).abs() < 1e-15);
+        assert!(erlang_a(-1.0, 180.0, 120.0, 10, 20.0).is_err());
+
+        // Without calls nobody waits and every (absent) call counts as answered in time.
+        let idle = erlang_a(0.0, 180.0, 120.0, 10, 20.0).unwrap();
+        assert_eq!(idle.probability_of_waiting, 0.0);
+        assert_eq!(idle.average_speed_of_answer, 0.0);
+        assert_eq!(idle.service_level, 1.0);
+        assert_eq!(calculate_agents(0.0, 180.0, 120.0, 20.0, 0.8, 10), Some(1));
+        assert!(erlang_a(0.05, 0.0, 120.0, 10, 20.0).is_err());
+    }
+
+    #[test]
+    fn test_calculate_agents() {
+        let agents = calculate_agents(1.0 / 18.0, 180.0, 60.0, 20.0, 0.8, 100).unwrap();
+        let metrics = erlang_a(1.0 / 18.0, 180.0, 60.0, agents, 20.0).unwrap();
+        assert!(metrics.service_level >= 0.8);
+        let fewer = erlang_a(1.0 / 18.0, 180.0, 60.0, agents - 1, 20.0).unwrap();
+        assert!(fewer.service_level < 0.8);
+        // Abandonment relieves the queue, so no more agents than Erlang C are needed.
+        assert!(agents <= erlang_c::calculate_agents(10.0, 180.0, 20.0, 0.8, 100).unwrap());
+        assert_eq!(
+            calculate_agents(1.0 / 18.0, 180.0, 60.0, 20.0, 0.8, 5),
+            None
+        );
+    }
+}
@@ -0,0 +1,247 @@
+// Erlang C (queued calls) calculations for contact-centre dimensioning.
+
+use crate::error::validate_traffic;
+use crate::{erlang_b, ErlangError};
+
+/// Calculates the probability that a call has to wait using the Erlang C formula.
+/// The value is derived from the Erlang B blocking probability of the same group.
+///
+/// # Arguments
+/// * `traffic` - The traffic load in Erlangs.
+/// * `agents` - The number of agents (servers) answering calls.
+///
+/// # Returns
+/// The probability that an arriving call is queued. Returns `1.0` when the
+/// system is overloaded (`agents <= traffic`) and the queue grows without bound.
+pub fn erlang_c(traffic: f64, agents: u32) -> f64 {
+    let agents_f = agents as f64;
+    if agents_f <= traffic {
+        return 1.0;
+    }
+
+    let blocking = erlang_b(traffic, agents);
+    agents_f * blocking / (agents_f - traffic * (1.0 - blocking))
+}
+
+/// Calculates the average speed of answer (ASA) for all offered calls.
+///
+/// # Arguments
+/// * `traffic` - The traffic load in Erlangs.
+/// * `agents` - The number of agents answering calls.
+/// * `average_handle_time` - Average handle time of a call in seconds.
+///
+/// # Returns
+/// The average waiting time in seconds, or `f64::INFINITY` if the system is overloaded.
+pub fn average_speed_of_answer(traffic: f64, agents: u32, average_handle_time: f64) -> f64 {
+    let agents_f = agents as f64;
+    if agents_f <= traffic {
+        return f64::INFINITY;
+    }
+
+    erlang_c(traffic, agents) * average_handle_time / (agents_f - traffic)
+}
+
+/// Calculates the service level, i.e. the fraction of calls answered within
+/// `target_answer_time` seconds.
+///
+/// # Arguments
+/// * `traffic` - The traffic load in Erlangs.
+/// * `agents` - The number of agents answering calls.
+/// * `average_handle_time` - Average handle time of a call in seconds.
+/// * `target_answer_time` - Answer time threshold in seconds.
+///
+/// # Returns
+/// The service level between 0 and 1. An overloaded system has a service level of `0.0`.
+pub fn service_level(
+    traffic: f64,
+    agents: u32,
+    average_handle_time: f64,
+    target_answer_time: f64,
+) -> f64 {
+    let agents_f = agents as f64;
+    if agents_f <= traffic {
+        return 0.0;
+    }
+
+    let exponent = -(agents_f - traffic) * target_answer_time / average_handle_time;
+    1.0 - erlang_c(traffic, agents) * exponent.exp()
+}
+
+/// Calculates the probability that a call has to wait using the Erlang C formula,
+/// validating the traffic first.
+///
+/// # Arguments
+/// * `traffic` - The traffic load in Erlangs.
+/// * `agents` - The number of agents (servers) answering calls.
+///
+/// # Returns
+/// The probability that an arriving call is queued, or `ErlangError::InvalidTraffic`
+/// for negative, NaN or infinite traffic.
+pub fn try_erlang_c(traffic: f64, agents: u32) -> Result<f64, ErlangError> {
+    Ok(erlang_c(validate_traffic(traffic)?, agents))
+}
+
+/// Iteratively calculates the minimum number of agents required to reach a target
+/// service level using the Erlang C formula.
+///
+/// This is a thin wrapper around `try_calculate_agents` that maps every error to `None`.
+///
+/// # Arguments
+/// * `traffic` - The traffic load in Erlangs.
+/// * `average_handle_time` - Average handle time of a call in seconds.
+/// * `target_answer_time` - Answer time threshold in seconds.
+/// * `target_service_level` - Desired fraction of calls answered within the threshold (between 0 and 1).
+/// * `agents_max` - Maximum number of agents to search for.
+///
+/// # Returns
+/// Returns the number of agents required to meet the service level, or `None`
+/// if the number of agents exceeds `agents_max` or an input is invalid.
+pub fn calculate_agents(
+    traffic: f64,
+    average_handle_time: f64,
+    target_answer_time: f64,
+    target_service_level: f64,
+    agents_max: u32,
+) -> Option<u32> {
+    try_calculate_agents(
+        traffic,
+        average_handle_time,
+        target_answer_time,
+        target_service_level,
+        agents_max,
+    )
+    .ok()
+}
+
+/// Iteratively calculates the minimum number of agents required to reach a target
+/// service level using the Erlang C formula, reporting why no agent count was found.
+///
+/// # Arguments
+/// * `traffic` - The traffic load in Erlangs.
+/// * `average_handle_time` - Average handle time of a call in seconds.
+/// * `target_answer_time` - Answer time threshold in seconds.
+/// * `target_service_level` - Desired fraction of calls answered within the threshold (between 0 and 1).
+/// * `agents_max` - Maximum number of agents to search for.
+///
+/// # Returns
+/// The number of agents required to meet the service level, or an error:
+/// * `ErlangError::InvalidTraffic` for negative, NaN or infinite traffic.
+/// * `ErlangError::InvalidParameter` for a handle time that is not finite and positive,
+///   a negative or non-finite answer time, or a service level outside `[0, 1]`.
+/// * `ErlangError::SearchLimitExceeded` if no agent count below `agents_max` meets the
+///   target; `best_blocking` is then the fraction of calls not answered in time with the
+///   most agents tried.
+pub fn try_calculate_agents(
+    traffic: f64,
+    average_handle_time: f64,
+    target_answer_time: f64,
+    target_service_level: f64,
+    agents_max: u32,
+) -> Result<u32, ErlangError> {
+    let traffic = validate_traffic(traffic)?;
+    if !(average_handle_time > 0.0 && average_handle_time.is_finite()) {
+        return Err(ErlangError::InvalidParameter(
+            "handle time must be finite and positive",
+        ));
+    }
+    if !(target_answer_time >= 0.0 && target_answer_time.is_finite()) {
+        return Err(ErlangError::InvalidParameter(
+            "answer time must be finite and non-negative",
+        ));
+    }
+    if !(0.0..=1.0).contains(&target_service_level) {
+        return Err(ErlangError::InvalidParameter(
+            "service level must be between 0 and 1",
+        ));
+    }
+
+    // Fewer agents than Erlangs can never serve the queue, so start just above the load.
+    let mut agents = (traffic.floor() as u32).saturating_add(1).max(1);
+    let mut best_level = 0.0;
+
+    while agents < agents_max {
+        let level = service_level(traffic, agents, average_handle_time, target_answer_time);
+        if level >= target_service_level {
+            return Ok(agents);
+        }
+        best_level = level;
+        agents += 1;
+    }
+
+    Err(ErlangError::SearchLimitExceeded {
+        channels_max: agents_max,
+        best_blocking: 1.0 - best_level,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_erlang_c() {
+        // 10 Erlangs on 11 agents is a textbook case with P(wait) ~= 0.6821.
+        let waiting = erlang_c(10.0, 11);
+        assert!((waiting - 0.6821).abs() < 1e-4);
+        assert_eq!(erlang_c(10.0, 10), 1.0);
+    }
+
+    #[test]
+    fn test_average_speed_of_answer_and_service_level() {
+        let asa = average_speed_of_answer(10.0, 12, 180.0);
+        assert!(asa > 0.0 && asa < 180.0);
+
+        let level = service_level(10.0, 12, 180.0, 20.0);
+        assert!(level > 0.0 && level < 1.0);
+        assert!(service_level(10.0, 13, 180.0, 20.0) > level);
+        assert_eq!(service_level(10.0, 9, 180.0, 20.0), 0.0);
+    }
+
+    #[test]
+    fn test_calculate_agents() {
+        // 100 calls/half-hour at 180 s AHT is 10 Erlangs; 80/20 needs 14 agents.
+        let agents = calculate_agents(10.0, 180.0, 20.0, 0.8, 100);
+        assert_eq!(agents, Some(14));
+        assert_eq!(calculate_agents(10.0, 180.0, 20.0, 0.8, 12), None);
+    }
+
+    #[test]
+    fn test_try_calculate_agents() {
+        assert_eq!(try_calculate_agents(10.0, 180.0, 20.0, 0.8, 100), Ok(14));
+        assert_eq!(try_erlang_c(10.0, 11), Ok(erlang_c(10.0, 11)));
+        assert_eq!(
+            try_erlang_c(-1.0, 11),
+            Err(ErlangError::InvalidTraffic(-1.0))
+        );
+        assert_eq!(
+            try_calculate_agents(-1.0, 180.0, 20.0, 0.8, 100),
+            Err(ErlangError::InvalidTraffic(-1.0))
+        );
+        assert!(try_calculate_agents(f64::NAN, 180.0, 20.0, 0.8, 100).is_err());
+        for (aht, answer, level) in [
+            (0.0, 20.0, 0.8),
+            (f64::INFINITY, 20.0, 0.8),
+            (180.0, -1.0, 0.8),
+            (180.0, 20.0, 1.5),
+            (180.0, 20.0, -0.1),
+            (180.0, 20.0, f64::NAN),
+        ] {
+            assert!(matches!(
+                try_calculate_agents(10.0, aht, answer, level, 100),
+                Err(ErlangError::InvalidParameter(_))
+            ));
+        }
+
+        match try_calculate_agents(10.0, 180.0, 20.0, 0.8, 12) {
+            Err(ErlangError::SearchLimitExceeded {
+                channels_max,
+                best_blocking,
+            }) => {
+                assert_eq!(channels_max, 12);
+                let level = service_level(10.0, 11, 180.0, 20.0);
+                assert!((best_blocking - (1.0 - level)).abs() < 1e-12);
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+}
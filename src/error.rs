@@ -0,0 +1,96 @@
+// Error type shared by the fallible calculation functions.
+
+use std::fmt;
+
+/// Errors reported by the `try_*` calculation functions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErlangError {
+    /// Traffic was negative, NaN or infinite.
+    InvalidTraffic(f64),
+    /// Blocking probability (grade of service) was not strictly between 0 and 1.
+    InvalidBlockingProbability(f64),
+    /// No channel count below `channels_max` met the target blocking probability.
+    SearchLimitExceeded {
+        /// The search limit that was reached.
+        channels_max: u32,
+        /// The lowest blocking probability reached within the limit.
+        best_blocking: f64,
+    },
+    /// An intermediate value overflowed the range of `f64`, or a span count the range of `u32`.
+    NumericOverflow,
+    /// A model parameter was out of range; the message names the parameter and constraint.
+    InvalidParameter(&'static str),
+}
+
+impl fmt::Display for ErlangError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErlangError::InvalidTraffic(traffic) => {
+                write!(f, "invalid traffic {traffic}: must be a finite, non-negative number of Erlangs")
+            }
+            ErlangError::InvalidBlockingProbability(probability) => {
+                write!(f, "invalid blocking probability {probability}: must be between 0 and 1")
+            }
+            ErlangError::SearchLimitExceeded {
+                channels_max,
+                best_blocking,
+            } => write!(
+                f,
+                "no channel count below {channels_max} meets the target (best blocking {best_blocking})"
+            ),
+            ErlangError::NumericOverflow => write!(f, "numeric overflow during calculation"),
+            ErlangError::InvalidParameter(message) => write!(f, "invalid parameter: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ErlangError {}
+
+/// Checks that traffic is a finite, non-negative number of Erlangs.
+pub(crate) fn validate_traffic(traffic: f64) -> Result<f64, ErlangError> {
+    if traffic.is_finite() && traffic >= 0.0 {
+        Ok(traffic)
+    } else {
+        Err(ErlangError::InvalidTraffic(traffic))
+    }
+}
+
+/// Checks that a blocking probability lies strictly between 0 and 1.
+pub(crate) fn validate_blocking_probability(probability: f64) -> Result<f64, ErlangError> {
+    if probability > 0.0 && probability < 1.0 {
+        Ok(probability)
+    } else {
+        Err(ErlangError::InvalidBlockingProbability(probability))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(validate_traffic(0.0), Ok(0.0));
+        assert_eq!(
+            validate_traffic(-1.0),
+            Err(ErlangError::InvalidTraffic(-1.0))
+        );
+        assert!(validate_traffic(f64::NAN).is_err());
+        assert!(validate_traffic(f64::INFINITY).is_err());
+        assert_eq!(validate_blocking_probability(0.01), Ok(0.01));
+        assert!(validate_blocking_probability(0.0).is_err());
+        assert!(validate_blocking_probability(1.0).is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        let error = ErlangError::SearchLimitExceeded {
+            channels_max: 10,
+            best_blocking: 0.25,
+        };
+        assert_eq!(
+            error.to_string(),
+            "no channel count below 10 meets the target (best blocking 0.25)"
+        );
+    }
+}
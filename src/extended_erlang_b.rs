@@ -0,0 +1,247 @@
+// Extended Erlang B calculations for traffic with redialling callers.
+
+use crate::error::{validate_blocking_probability, validate_traffic};
+use crate::{erlang_b, ErlangError};
+
+/// Convergence threshold, in Erlangs, for the effective offered traffic.
+const TRAFFIC_TOLERANCE: f64 = 1e-9;
+
+/// Maximum number of fixed-point iterations before giving up on convergence.
+const MAX_ITERATIONS: u32 = 10_000;
+
+/// Result of an Extended Erlang B evaluation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExtendedErlangB {
+    /// Blocking probability seen by the effective offered traffic.
+    pub blocking: f64,
+    /// Effective offered traffic in Erlangs, i.e. fresh traffic plus redial attempts.
+    pub offered_traffic: f64,
+    /// Number of fixed-point iterations performed.
+    pub iterations: u32,
+}
+
+/// Calculates the blocking probability using the Extended Erlang B model, where a share
+/// of the blocked callers immediately redial and add to the offered traffic.
+///
+/// This is a thin wrapper around `try_extended_erlang_b` that maps every error to `None`.
+///
+/// # Arguments
+/// * `traffic` - The fresh traffic load in Erlangs.
+/// * `channels` - The number of communication channels.
+/// * `recall_factor` - Fraction of blocked calls that are retried (between 0 and 1).
+///
+/// # Returns
+/// The final blocking and inflated offered traffic, or `None` if an input is invalid or
+/// the iteration does not converge.
+pub fn extended_erlang_b(
+    traffic: f64,
+    channels: u32,
+    recall_factor: f64,
+) -> Option<ExtendedErlangB> {
+    try_extended_erlang_b(traffic, channels, recall_factor).ok()
+}
+
+/// Calculates the blocking probability using the Extended Erlang B model, reporting
+/// invalid inputs.
+///
+/// Each iteration applies `erlang_b` to the current offered traffic and recomputes the
+/// traffic as `traffic + recall_factor × offered × blocking` until it settles.
+///
+/// # Arguments
+/// * `traffic` - The fresh traffic load in Erlangs.
+/// * `channels` - The number of communication channels.
+/// * `recall_factor` - Fraction of blocked calls that are retried (between 0 and 1).
+///
+/// # Returns
+/// The final blocking and inflated offered traffic, or an error:
+/// * `ErlangError::InvalidTraffic` for negative, NaN or infinite traffic.
+/// * `ErlangError::InvalidParameter` if `recall_factor` is outside `[0, 1)` or the
+///   iteration does not converge.
+pub fn try_extended_erlang_b(
+    traffic: f64,
+    channels: u32,
+    recall_factor: f64,
+) -> Result<ExtendedErlangB, ErlangError> {
+    let traffic = validate_traffic(traffic)?;
+    validate_recall_factor(recall_factor)?;
+
+    let mut offered_traffic = traffic;
+    let mut blocking = erlang_b(offered_traffic, channels);
+
+    for iteration in 1..=MAX_ITERATIONS {
+        let next_traffic = traffic + recall_factor * offered_traffic * blocking;
+        let delta = (next_traffic - offered_traffic).abs();
+        offered_traffic = next_traffic;
+        blocking = erlang_b(offered_traffic, channels);
+
+        if delta < TRAFFIC_TOLERANCE {
+            return Ok(ExtendedErlangB {
+                blocking,
+                offered_traffic,
+                iterations: iteration,
+            });
+        }
+    }
+
+    Err(ErlangError::InvalidParameter(
+        "recall factor is too close to 1 for the offered traffic to converge",
+    ))
+}
+
+fn validate_recall_factor(recall_factor: f64) -> Result<f64, ErlangError> {
+    if (0.0..1.0).contains(&recall_factor) {
+        Ok(recall_factor)
+    } else {
+        Err(ErlangError::InvalidParameter(
+            "recall factor must be at least 0 and below 1",
+        ))
+    }
+}
+
+/// Iteratively calculates the number of channels required to satisfy a given blocking
+/// probability when blocked callers redial, using the Extended Erlang B model.
+///
+/// This is a thin wrapper around `try_calculate_extended_e1_channels` that maps every
+/// error to `None`.
+///
+/// # Arguments
+/// * `traffic` - The fresh traffic load in Erlangs.
+/// * `recall_factor` - Fraction of blocked calls that are retried (between 0 and 1).
+/// * `blocking_probability` - Desired blocking probability (between 0 and 1).
+/// * `channels_max` - Maximum number of channels to search for.
+///
+/// # Returns
+/// Returns the number of channels required to meet the blocking probability, or `None`
+/// if the number of channels exceeds `channels_max` or an input is invalid.
+pub fn calculate_extended_e1_channels(
+    traffic: f64,
+    recall_factor: f64,
+    blocking_probability: f64,
+    channels_max: u32,
+) -> Option<u32> {
+    try_calculate_extended_e1_channels(traffic, recall_factor, blocking_probability, channels_max)
+        .ok()
+}
+
+/// Iteratively calculates the number of channels required to satisfy a given blocking
+/// probability with the Extended Erlang B model, reporting why no channel count was found.
+///
+/// # Arguments
+/// * `traffic` - The fresh traffic load in Erlangs.
+/// * `recall_factor` - Fraction of blocked calls that are retried (between 0 and 1).
+/// * `blocking_probability` - Desired blocking probability (between 0 and 1).
+/// * `channels_max` - Maximum number of channels to search for.
+///
+/// # Returns
+/// The number of channels required to meet the blocking probability, or an error:
+/// * `ErlangError::InvalidTraffic` for negative, NaN or infinite traffic.
+/// * `ErlangError::InvalidBlockingProbability` if `blocking_probability` is not strictly
+///   between 0 and 1.
+/// * `ErlangError::InvalidParameter` if `recall_factor` is outside `[0, 1)` or the
+///   iteration does not converge.
+/// * `ErlangError::SearchLimitExceeded` if no channel count below `channels_max` meets
+///   the target.
+pub fn try_calculate_extended_e1_channels(
+    traffic: f64,
+    recall_factor: f64,
+    blocking_probability: f64,
+    channels_max: u32,
+) -> Result<u32, ErlangError> {
+    let traffic = validate_traffic(traffic)?;
+    let blocking_probability = validate_blocking_probability(blocking_probability)?;
+    validate_recall_factor(recall_factor)?;
+
+    let mut channels = 1;
+    let mut best_blocking = 1.0;
+
+    while channels < channels_max {
+        let result = try_extended_erlang_b(traffic, channels, recall_factor)?;
+        if result.blocking <= blocking_probability {
+            return Ok(channels);
+        }
+        best_blocking = result.blocking;
+        channels += 1;
+    }
+
+    Err(ErlangError::SearchLimitExceeded {
+        channels_max,
+        best_blocking,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculate_e1_channels;
+
+    #[test]
+    fn test_extended_erlang_b() {
+        let result = extended_erlang_b(20.0, 20, 0.5).unwrap();
+        assert!(result.offered_traffic > 20.0);
+        assert!(result.blocking > erlang_b(20.0, 20));
+
+        // The reported traffic is a fixed point of the redial equation.
+        let expected = 20.0 + 0.5 * result.offered_traffic * result.blocking;
+        assert!((result.offered_traffic - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_extended_erlang_b_without_recall() {
+        let result = extended_erlang_b(20.0, 20, 0.0).unwrap();
+        assert_eq!(result.offered_traffic, 20.0);
+        assert_eq!(result.blocking, erlang_b(20.0, 20));
+        assert!(extended_erlang_b(20.0, 20, 1.0).is_none());
+    }
+
+    #[test]
+    fn test_calculate_extended_e1_channels() {
+        let plain = calculate_e1_channels(20.0, 0.01, 1_000).unwrap();
+        let extended = calculate_extended_e1_channels(20.0, 0.8, 0.01, 1_000).unwrap();
+        assert!(extended >= plain);
+        assert!(extended_erlang_b(20.0, extended, 0.8).unwrap().blocking <= 0.01);
+    }
+
+    #[test]
+    fn test_try_calculate_extended_e1_channels() {
+        assert_eq!(
+            try_calculate_extended_e1_channels(20.0, 0.8, 0.01, 1_000).ok(),
+            calculate_extended_e1_channels(20.0, 0.8, 0.01, 1_000)
+        );
+        assert_eq!(
+            try_extended_erlang_b(-1.0, 20, 0.5),
+            Err(ErlangError::InvalidTraffic(-1.0))
+        );
+        assert_eq!(
+            try_calculate_extended_e1_channels(f64::INFINITY, 0.5, 0.01, 100),
+            Err(ErlangError::InvalidTraffic(f64::INFINITY))
+        );
+        assert_eq!(
+            try_calculate_extended_e1_channels(20.0, 0.5, 1.5, 100),
+            Err(ErlangError::InvalidBlockingProbability(1.5))
+        );
+        for recall_factor in [-0.1, 1.0, f64::NAN] {
+            assert!(matches!(
+                try_extended_erlang_b(20.0, 20, recall_factor),
+                Err(ErlangError::InvalidParameter(_))
+            ));
+            assert!(matches!(
+                try_calculate_extended_e1_channels(20.0, recall_factor, 0.01, 100),
+                Err(ErlangError::InvalidParameter(_))
+            ));
+        }
+
+        match try_calculate_extended_e1_channels(20.0, 0.8, 0.01, 20) {
+            Err(ErlangError::SearchLimitExceeded {
+                channels_max,
+                best_blocking,
+            }) => {
+                assert_eq!(channels_max, 20);
+                assert_eq!(
+                    best_blocking,
+                    extended_erlang_b(20.0, 19, 0.8).unwrap().blocking
+                );
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+}
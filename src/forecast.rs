@@ -0,0 +1,365 @@
+// Busy-hour traffic forecasting from historical series, with prediction intervals.
+
+use crate::error::{validate_blocking_probability, validate_traffic};
+use crate::stats::{inverse_normal_cdf, student_t_quantile};
+use crate::{try_calculate_e1_channels, ErlangError};
+
+/// One forecast step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ForecastPoint {
+    /// Steps ahead of the last observation, starting at 1.
+    pub step: u32,
+    /// Expected traffic in Erlangs.
+    pub traffic: f64,
+    /// Standard error of a new observation at this step.
+    pub standard_error: f64,
+}
+
+/// Traffic forecast with the spread needed for prediction intervals.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Forecast {
+    /// One point per step of the horizon.
+    pub points: Vec<ForecastPoint>,
+    /// Degrees of freedom of the Student t prediction distribution, or `None` when the
+    /// errors are taken as normal.
+    pub degrees_of_freedom: Option<f64>,
+}
+
+impl Forecast {
+    fn quantile(&self, probability: f64) -> f64 {
+        match self.degrees_of_freedom {
+            Some(degrees_of_freedom) => student_t_quantile(probability, degrees_of_freedom),
+            None => inverse_normal_cdf(probability),
+        }
+    }
+
+    /// The traffic at a percentile of the prediction distribution for each step, e.g.
+    /// `0.95` for the 95th percentile. Negative traffic is clamped to zero.
+    pub fn percentile(&self, probability: f64) -> Vec<f64> {
+        let quantile = self.quantile(probability);
+        self.points
+            .iter()
+            .map(|point| (point.traffic + quantile * point.standard_error).max(0.0))
+            .collect()
+    }
+
+    /// The lower and upper bounds of the central prediction interval for each step, e.g.
+    /// `0.95` for a 95% interval.
+    pub fn prediction_interval(&self, confidence: f64) -> Vec<(f64, f64)> {
+        let tail = (1.0 - confidence) / 2.0;
+        self.percentile(tail)
+            .into_iter()
+            .zip(self.percentile(1.0 - tail))
+            .collect()
+    }
+
+    /// Calculates the channels required at each step when dimensioning for a percentile of
+    /// the forecast rather than its mean.
+    ///
+    /// # Arguments
+    /// * `probability` - Forecast percentile to dimension for (between 0 and 1), e.g. `0.95`.
+    /// * `blocking_probability` - Desired blocking probability (between 0 and 1).
+    /// * `channels_max` - Maximum number of channels to search for.
+    ///
+    /// # Returns
+    /// The channels per step, `ErlangError::InvalidParameter` for a percentile outside
+    /// (0, 1), or any error from `try_calculate_e1_channels`.
+    pub fn calculate_channels(
+        &self,
+        probability: f64,
+        blocking_probability: f64,
+        channels_max: u32,
+    ) -> Result<Vec<u32>, ErlangError> {
+        if !(probability > 0.0 && probability < 1.0) {
+            return Err(ErlangError::InvalidParameter(
+                "forecast percentile must be between 0 and 1",
+            ));
+        }
+        validate_blocking_probability(blocking_probability)?;
+        self.percentile(probability)
+            .into_iter()
+            .map(|traffic| try_calculate_e1_channels(traffic, blocking_probability, channels_max))
+            .collect()
+    }
+}
+
+fn validate_history(
+    history: &[f64],
+    minimum: usize,
+    message: &'static str,
+) -> Result<(), ErlangError> {
+    if history.len() < minimum {
+        return Err(ErlangError::InvalidParameter(message));
+    }
+    for &traffic in history {
+        validate_traffic(traffic)?;
+    }
+    Ok(())
+}
+
+/// Forecasts traffic by fitting a straight line to the history with least squares.
+///
+/// The prediction interval follows the classical regression formula: a new observation
+/// at time `t` has standard error `s × √(1 + 1/n + (t − t̄)² / Σ(tᵢ − t̄)²)`, with `n − 2`
+/// degrees of freedom.
+///
+/// # Arguments
+/// * `history` - Busy-hour traffic in Erlangs, one value per period, oldest first.
+/// * `horizon` - Number of periods to forecast.
+///
+/// # Returns
+/// The forecast, `ErlangError::InvalidParameter` for fewer than three observations, or
+/// `ErlangError::InvalidTraffic` for a negative or non-finite observation.
+pub fn linear_regression(history: &[f64], horizon: u32) -> Result<Forecast, ErlangError> {
+    validate_history(
+        history,
+        3,
+        "linear regression needs at least three observations",
+    )?;
+
+    let n = history.len() as f64;
+    let time_mean = (n - 1.0) / 2.0;
+    let traffic_mean = history.iter().sum::<f64>() / n;
+    let mut time_squares = 0.0;
+    let mut cross = 0.0;
+    for (time, &traffic) in history.iter().enumerate() {
+        let offset = time as f64 - time_mean;
+        time_squares += offset * offset;
+        cross += offset * (traffic - traffic_mean);
+    }
+    let slope = cross / time_squares;
+    let intercept = traffic_mean - slope * time_mean;
+
+    let residual_squares: f64 = history
+        .iter()
+        .enumerate()
+        .map(|(time, &traffic)| (traffic - intercept - slope * time as f64).powi(2))
+        .sum();
+    let residual_deviation = (residual_squares / (n - 2.0)).sqrt();
+
+    let points = (1..=horizon)
+        .map(|step| {
+            let time = n - 1.0 + step as f64;
+            let leverage = 1.0 / n + (time - time_mean).powi(2) / time_squares;
+            ForecastPoint {
+                step,
+                traffic: intercept + slope * time,
+                standard_error: residual_deviation * (1.0 + leverage).sqrt(),
+            }
+        })
+        .collect();
+
+    Ok(Forecast {
+        points,
+        degrees_of_freedom: Some(n - 2.0),
+    })
+}
+
+/// Smoothing parameters of additive Holt-Winters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HoltWinters {
+    /// Level smoothing (between 0 and 1).
+    pub alpha: f64,
+    /// Trend smoothing (between 0 and 1).
+    pub beta: f64,
+    /// Seasonal smoothing (between 0 and 1).
+    pub gamma: f64,
+    /// Periods per season, e.g. 12 for monthly data with a yearly cycle.
+    pub season_length: usize,
+}
+
+impl Default for HoltWinters {
+    /// Moderate smoothing of monthly data with a yearly cycle.
+    fn default() -> Self {
+        HoltWinters {
+            alpha: 0.3,
+            beta: 0.1,
+            gamma: 0.1,
+            season_length: 12,
+        }
+    }
+}
+
+/// Forecasts traffic with additive Holt-Winters seasonal smoothing.
+///
+/// The level, trend and seasonal indices start from the first two seasons and are then
+/// updated once per observation. The prediction variance at `h` steps is that of the
+/// equivalent ETS(A,A,A) state space model (Hyndman et al., 2008),
+/// `σ² × (1 + (h − 1)(α² + αβ'h + β'²h(2h − 1)/6) + kγ'(2α + γ' + β'm(k + 1)))` with
+/// `β' = αβ`, `γ' = γ(1 − α)`, `k = ⌊(h − 1)/m⌋`, and `σ²` the mean squared one-step error.
+///
+/// # Arguments
+/// * `history` - Busy-hour traffic in Erlangs, one value per period, oldest first.
+/// * `horizon` - Number of periods to forecast.
+/// * `parameters` - Smoothing parameters and season length.
+///
+/// # Returns
+/// The forecast, `ErlangError::InvalidParameter` for a smoothing parameter outside 0 to 1,
+/// a zero season length or fewer than two full seasons of history, or
+/// `ErlangError::InvalidTraffic` for a negative or non-finite observation.
+pub fn holt_winters(
+    history: &[f64],
+    horizon: u32,
+    parameters: HoltWinters,
+) -> Result<Forecast, ErlangError> {
+    let HoltWinters {
+        alpha,
+        beta,
+        gamma,
+        season_length,
+    } = parameters;
+    if ![alpha, beta, gamma]
+        .iter()
+        .all(|parameter| (0.0..=1.0).contains(parameter))
+    {
+        return Err(ErlangError::InvalidParameter(
+            "smoothing parameters must be between 0 and 1",
+        ));
+    }
+    if season_length == 0 {
+        return Err(ErlangError::InvalidParameter(
+            "season length must be at least 1",
+        ));
+    }
+    validate_history(
+        history,
+        2 * season_length,
+        "Holt-Winters needs at least two full seasons of history",
+    )?;
+
+    // Level and trend at the end of the first season, seasonal indices around the trend.
+    let m = season_length as f64;
+    let first_mean = history[..season_length].iter().sum::<f64>() / m;
+    let second_mean = history[season_length..2 * season_length]
+        .iter()
+        .sum::<f64>()
+        / m;
+    let mut trend = (second_mean - first_mean) / m;
+    let mut level = first_mean + trend * (m - 1.0) / 2.0;
+    let mut seasonal: Vec<f64> = history[..season_length]
+        .iter()
+        .enumerate()
+        .map(|(time, &traffic)| traffic - first_mean - trend * (time as f64 - (m - 1.0) / 2.0))
+        .collect();
+
+    let mut squared_errors = 0.0;
+    for (time, &traffic) in history.iter().enumerate().skip(season_length) {
+        let index = time % season_length;
+        let error = traffic - (level + trend + seasonal[index]);
+        squared_errors += error * error;
+
+        let previous_level = level;
+        level = alpha * (traffic - seasonal[index]) + (1.0 - alpha) * (level + trend);
+        trend = beta * (level - previous_level) + (1.0 - beta) * trend;
+        seasonal[index] = gamma * (traffic - level) + (1.0 - gamma) * seasonal[index];
+    }
+    let variance = squared_errors / (history.len() - season_length) as f64;
+
+    let trend_gain = alpha * beta;
+    let seasonal_gain = gamma * (1.0 - alpha);
+    let points = (1..=horizon)
+        .map(|step| {
+            let h = step as f64;
+            let k = ((step - 1) as usize / season_length) as f64;
+            let index = (history.len() + step as usize - 1) % season_length;
+            let factor = 1.0
+                + (h - 1.0)
+                    * (alpha * alpha
+                        + alpha * trend_gain * h
+                        + trend_gain * trend_gain * h * (2.0 * h - 1.0) / 6.0)
+                + k * seasonal_gain * (2.0 * alpha + seasonal_gain + trend_gain * m * (k + 1.0));
+            ForecastPoint {
+                step,
+                traffic: level + h * trend + seasonal[index],
+                standard_error: (variance * factor).sqrt(),
+            }
+        })
+        .collect();
+
+    Ok(Forecast {
+        points,
+        degrees_of_freedom: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculate_e1_channels;
+
+    const HISTORY: [f64; 8] = [10.2, 11.9, 14.1, 15.8, 18.3, 19.7, 22.4, 23.6];
+
+    #[test]
+    fn test_linear_regression() {
+        let forecast = linear_regression(&HISTORY, 3).unwrap();
+        assert_eq!(forecast.degrees_of_freedom, Some(6.0));
+        // Least-squares fit y = 10.1 + 1.9714t with s = 0.3071.
+        let first = forecast.points[0];
+        assert_eq!(first.step, 1);
+        assert!((first.traffic - 25.871_428_571_428_57).abs() < 1e-9);
+        assert!((first.standard_error - 0.389_269_331_241_105_4).abs() < 1e-9);
+
+        let (lower, upper) = forecast.prediction_interval(0.95)[0];
+        assert!((upper - first.traffic - 2.446_9 * first.standard_error).abs() < 1e-3);
+        assert!((first.traffic - lower - (upper - first.traffic)).abs() < 1e-12);
+        assert!(forecast.points[2].standard_error > first.standard_error);
+
+        assert!(linear_regression(&HISTORY[..2], 3).is_err());
+        assert_eq!(
+            linear_regression(&[1.0, -1.0, 2.0], 1),
+            Err(ErlangError::InvalidTraffic(-1.0))
+        );
+    }
+
+    #[test]
+    fn test_holt_winters_exact_pattern() {
+        // A linear trend plus a fixed seasonal pattern is forecast without error.
+        let pattern = [3.0, -1.0, 4.0, -6.0];
+        let series: Vec<f64> = (0..16)
+            .map(|time| 40.0 + 0.5 * time as f64 + pattern[time % 4])
+            .collect();
+        let parameters = HoltWinters {
+            season_length: 4,
+            ..HoltWinters::default()
+        };
+        let forecast = holt_winters(&series, 6, parameters).unwrap();
+        for point in &forecast.points {
+            let time = 15 + point.step as usize;
+            let expected = 40.0 + 0.5 * time as f64 + pattern[time % 4];
+            assert!((point.traffic - expected).abs() < 1e-9);
+            assert!(point.standard_error < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_holt_winters_intervals() {
+        let series: Vec<f64> = (0..36)
+            .map(|time| {
+                let noise = [0.4, -0.3, 0.1, -0.5, 0.2, 0.3][time % 6];
+                30.0 + 0.2 * time as f64
+                    + 5.0 * (time as f64 * std::f64::consts::PI / 6.0).sin()
+                    + noise
+            })
+            .collect();
+        let forecast = holt_winters(&series, 24, HoltWinters::default()).unwrap();
+        assert_eq!(forecast.degrees_of_freedom, None);
+        let errors: Vec<f64> = forecast.points.iter().map(|p| p.standard_error).collect();
+        assert!(errors.windows(2).all(|pair| pair[1] > pair[0]));
+        // Two steps ahead the error is ε₂ + (α + αβ)ε₁.
+        let gain: f64 = 0.3 + 0.3 * 0.1;
+        assert!((errors[1] / errors[0] - (1.0 + gain * gain).sqrt()).abs() < 1e-12);
+
+        let mean = forecast.percentile(0.5);
+        let upper = forecast.percentile(0.95);
+        assert!(mean.iter().zip(&upper).all(|(mean, upper)| upper > mean));
+        let channels = forecast.calculate_channels(0.95, 0.01, 1_000).unwrap();
+        assert_eq!(
+            Some(channels[0]),
+            calculate_e1_channels(upper[0], 0.01, 1_000)
+        );
+        assert!(forecast.calculate_channels(1.0, 0.01, 1_000).is_err());
+
+        let short = holt_winters(&series[..20], 6, HoltWinters::default());
+        assert!(matches!(short, Err(ErlangError::InvalidParameter(_))));
+    }
+}
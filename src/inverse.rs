@@ -0,0 +1,108 @@
+// Inverse Erlang B: maximum traffic carried by a channel group at a given grade of service.
+
+use crate::erlang_b;
+
+/// Relative tolerance on the traffic returned by `max_traffic`. The solver stops once
+/// the last correction is smaller than `TRAFFIC_TOLERANCE × traffic`.
+pub const TRAFFIC_TOLERANCE: f64 = 1e-10;
+
+/// Maximum number of Newton/bisection iterations performed by `max_traffic`.
+pub const MAX_ITERATIONS: u32 = 200;
+
+/// Result of inverting the Erlang B formula for traffic.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InverseErlangB {
+    /// Largest traffic in Erlangs for which the blocking stays at the target.
+    pub traffic: f64,
+    /// Number of Newton/bisection iterations used after bracketing.
+    pub iterations: u32,
+}
+
+/// Calculates the maximum traffic a group of channels can be offered while keeping the
+/// Erlang B blocking probability at or below a target.
+///
+/// The root is first bracketed by doubling an upper bound, then refined with Newton steps
+/// using `dB/dA = B × (N/A − 1 + B)`. Any Newton step that leaves the bracket is replaced
+/// by a bisection step, so the solver always converges.
+///
+/// # Arguments
+/// * `channels` - The number of communication channels.
+/// * `blocking_probability` - Target blocking probability (between 0 and 1).
+///
+/// # Returns
+/// The traffic in Erlangs (within `TRAFFIC_TOLERANCE`) and the iterations used, or `None`
+/// if `channels` is zero, the target is outside (0, 1), or the solver does not converge.
+pub fn max_traffic(channels: u32, blocking_probability: f64) -> Option<InverseErlangB> {
+    if channels == 0 || !(blocking_probability > 0.0 && blocking_probability < 1.0) {
+        return None;
+    }
+
+    let n = channels as f64;
+    let mut low = 0.0;
+    let mut high = n;
+    while erlang_b(high, channels) < blocking_probability {
+        low = high;
+        high *= 2.0;
+        if !high.is_finite() {
+            return None;
+        }
+    }
+
+    let mut traffic = 0.5 * (low + high);
+    for iteration in 1..=MAX_ITERATIONS {
+        let blocking = erlang_b(traffic, channels);
+        let error = blocking - blocking_probability;
+        if error < 0.0 {
+            low = traffic;
+        } else {
+            high = traffic;
+        }
+
+        let slope = blocking * (n / traffic - 1.0 + blocking);
+        let mut next = traffic - error / slope;
+        if !(next > low && next < high) {
+            next = 0.5 * (low + high);
+        }
+
+        let step = (next - traffic).abs();
+        traffic = next;
+        if step <= TRAFFIC_TOLERANCE * traffic {
+            return Some(InverseErlangB {
+                traffic,
+                iterations: iteration,
+            });
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_traffic() {
+        // Published Erlang B tables: 10 channels carry 4.46 Erlangs at 1% blocking.
+        let result = max_traffic(10, 0.01).unwrap();
+        assert!((result.traffic - 4.4612).abs() < 1e-4);
+        assert!(result.iterations > 0);
+
+        let blocking = erlang_b(result.traffic, 10);
+        assert!((blocking - 0.01).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_max_traffic_large_group() {
+        let result = max_traffic(1_000, 0.01).unwrap();
+        assert!(result.traffic > 950.0 && result.traffic < 1_000.0);
+        assert!((erlang_b(result.traffic, 1_000) - 0.01).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_max_traffic_invalid() {
+        assert!(max_traffic(0, 0.01).is_none());
+        assert!(max_traffic(10, 0.0).is_none());
+        assert!(max_traffic(10, 1.0).is_none());
+    }
+}
@@ -1,7 +1,37 @@
 // Erlang E1 Channels Calculation Library without external dependencies.
 
+pub mod bandwidth;
+pub mod batch;
+pub mod busy_hour;
+pub mod cdr;
+pub mod continuous;
+mod csv;
+pub mod economics;
+pub mod engset;
+pub mod erlang_a;
+pub mod erlang_c;
+mod error;
+pub mod extended_erlang_b;
+pub mod forecast;
+pub mod inverse;
+pub mod multirate;
+pub mod network;
+pub mod overflow;
+pub mod planning;
+pub mod profile;
+pub mod sensitivity;
+pub mod simulation;
+mod stats;
+pub mod table;
+pub mod trunk;
+
+pub use error::ErlangError;
+use error::{validate_blocking_probability, validate_traffic};
+use profile::{TimeUnit, TrafficProfile};
+
 /// Calculates the blocking probability using the Erlang B formula.
-/// This iterative approach sums up traffic for every available channel.
+///
+/// This is a thin wrapper around `try_erlang_b` that returns `NaN` for invalid traffic.
 ///
 /// # Arguments
 /// * `traffic` - The traffic load in Erlangs.
@@ -10,17 +40,106 @@
 /// # Returns
 /// The probability that all channels are busy (blocking probability).
 pub fn erlang_b(traffic: f64, channels: u32) -> f64 {
+    try_erlang_b(traffic, channels).unwrap_or(f64::NAN)
+}
+
+/// Calculates the blocking probability using the Erlang B formula, validating the input.
+///
+/// Only the channels within about `10√A` of the traffic `A` affect the result to machine
+/// precision, so the work is proportional to `√A` rather than to the channel count:
+///
+/// * Below that window, `1/B(n) = 1 + n/A × (1 + (n − 1)/A × (1 + …))` is expanded from
+///   the top and truncated once the remaining terms fall below machine precision. The
+///   terms shrink at least geometrically, by `n/A ≤ 1 − 10/√A`.
+/// * From there the recurrence `B(n) = A × B(n − 1) / (n + A × B(n − 1))` runs up to the
+///   channel count, stopping early if the blocking underflows to zero. Each step scales
+///   the relative error carried in by `n / (n + A × B) < 1`, so rounding never grows.
+///
+/// The relative error is at most `4ε` per term and step, `ε` being `f64::EPSILON`. This
+/// bound stays below 1e-10 for traffic up to 10^6 Erlangs at any channel count, and
+/// measured errors against high-precision references are around 1e-15; results in the
+/// subnormal range (below about 1e-308) lose precision gradually.
+///
+/// # Arguments
+/// * `traffic` - The traffic load in Erlangs (finite and non-negative).
+/// * `channels` - The number of communication channels.
+///
+/// # Returns
+/// The probability that all channels are busy, or `ErlangError::InvalidTraffic`.
+pub fn try_erlang_b(traffic: f64, channels: u32) -> Result<f64, ErlangError> {
+    validate_traffic(traffic)?;
+    if traffic == 0.0 {
+        return Ok(if channels == 0 { 1.0 } else { 0.0 });
+    }
+
+    let window = (10.0 * traffic.sqrt()).ceil();
+    let start = (traffic.floor() - window).clamp(0.0, channels as f64) as u32;
+
+    let mut blocking = 1.0 / inverse_erlang_b_expansion(traffic, start);
+    for n in start + 1..=channels {
+        blocking = traffic * blocking / (n as f64 + traffic * blocking);
+        if blocking == 0.0 {
+            break;
+        }
+    }
+
+    Ok(blocking)
+}
+
+/// Evaluates `1/B(n) = 1 + n/A × (1 + (n − 1)/A × (1 + …))` from the top, truncated once
+/// the remaining terms fall below machine precision. `channels` must not exceed the
+/// traffic, which must be positive; the work is about `10√A` terms at most.
+fn inverse_erlang_b_expansion(traffic: f64, channels: u32) -> f64 {
     let mut inverse_b = 1.0;
+    let mut term = 1.0;
+    for n in (1..=channels).rev() {
+        term *= n as f64 / traffic;
+        inverse_b += term;
+        // The remaining terms sum to less than `term × (n − 1) / (A − n + 1)`.
+        if term * traffic < f64::EPSILON / 2.0 * inverse_b * (traffic - n as f64) {
+            break;
+        }
+    }
+    inverse_b
+}
 
-    for n in 1..=channels {
-        inverse_b = 1.0 + inverse_b * (n as f64 / traffic);
+/// Approximates the Erlang B formula for large traffic with Jagerman's asymptotic
+/// expansion, without any recurrence over the channels.
+///
+/// With `N = A + β√A` the expansion is
+/// `1/B ≈ √A × R(β) + (4 − β² + β(3 − β²) × R(β)) / 6`, where `R = Φ/φ` is the ratio of
+/// the standard normal distribution and density functions. The relative error falls like
+/// `1/A`: about 1e-5 at 10^4 Erlangs and 1e-7 at 10^6 Erlangs for channel counts within
+/// a few `√A` of the traffic, growing further out in the tail.
+///
+/// # Arguments
+/// * `traffic` - The traffic load in Erlangs.
+/// * `channels` - The number of communication channels.
+///
+/// # Returns
+/// The approximate blocking probability, or `NaN` for invalid traffic.
+pub fn erlang_b_jagerman(traffic: f64, channels: u32) -> f64 {
+    if validate_traffic(traffic).is_err() {
+        return f64::NAN;
+    }
+    if traffic == 0.0 {
+        return if channels == 0 { 1.0 } else { 0.0 };
     }
 
-    1.0 / inverse_b
+    let root = traffic.sqrt();
+    let beta = (channels as f64 - traffic) / root;
+    let ratio = stats::normal_cdf_pdf_ratio(beta);
+    if ratio.is_infinite() {
+        return 0.0;
+    }
+    let inverse_b = root * ratio + (4.0 - beta * beta + beta * (3.0 - beta * beta) * ratio) / 6.0;
+    (1.0 / inverse_b).min(1.0)
 }
 
-/// Iteratively calculates the number of E1 voice channels required to satisfy
-/// a given blocking probability using the Erlang B formula.
+/// Calculates the number of E1 voice channels required to satisfy a given blocking
+/// probability using the Erlang B formula.
+///
+/// This is a thin wrapper around `try_calculate_e1_channels` that maps every error to `None`.
 ///
 /// # Arguments
 /// * `traffic` - The traffic load in Erlangs.
@@ -29,35 +148,220 @@ pub fn erlang_b(traffic: f64, channels: u32) -> f64 {
 ///
 /// # Returns
 /// Returns the number of channels required to meet the blocking probability, or `None`
-/// if the number of channels exceeds `channels_max`.
+/// if the number of channels exceeds `channels_max` or the input is invalid.
 pub fn calculate_e1_channels(
     traffic: f64,
     blocking_probability: f64,
     channels_max: u32,
 ) -> Option<u32> {
-    let mut channels = 1;
+    try_calculate_e1_channels(traffic, blocking_probability, channels_max).ok()
+}
+
+/// Calculates the number of E1 voice channels required to satisfy a given blocking
+/// probability using the Erlang B formula, validating the input.
+///
+/// The Erlang B recurrence is evaluated once, in a single pass over the channel count,
+/// and the search stops at the first count meeting the target. This gives the same
+/// result as calling `erlang_b` for every candidate, in linear instead of quadratic time.
+///
+/// # Arguments
+/// * `traffic` - The traffic load in Erlangs (finite and non-negative).
+/// * `blocking_probability` - Desired blocking probability (strictly between 0 and 1).
+/// * `channels_max` - Maximum number of channels to search for (exclusive).
+///
+/// # Returns
+/// The number of channels required, `ErlangError::SearchLimitExceeded` with the best
+/// blocking reached if no count below `channels_max` suffices, or an input error.
+pub fn try_calculate_e1_channels(
+    traffic: f64,
+    blocking_probability: f64,
+    channels_max: u32,
+) -> Result<u32, ErlangError> {
+    validate_traffic(traffic)?;
+    validate_blocking_probability(blocking_probability)?;
+
+    let mut inverse_b = 1.0;
+
+    for channels in 1..channels_max {
+        inverse_b = 1.0 + inverse_b * (channels as f64 / traffic);
+        if 1.0 / inverse_b <= blocking_probability {
+            return Ok(channels);
+        }
+    }
+
+    Err(ErlangError::SearchLimitExceeded {
+        channels_max,
+        best_blocking: 1.0 / inverse_b,
+    })
+}
+
+/// Calculates the number of E1 voice channels required to satisfy a given blocking
+/// probability, starting the search from an estimate of the answer.
+///
+/// This is a thin wrapper around `try_calculate_e1_channels_from` that maps every error to
+/// `None`.
+///
+/// # Arguments
+/// * `traffic` - The traffic load in Erlangs.
+/// * `blocking_probability` - Desired blocking probability (between 0 and 1).
+/// * `channels_max` - Maximum number of channels to search for.
+/// * `estimate` - Starting channel count, or `None` to use `square_root_staffing_estimate`.
+///
+/// # Returns
+/// Returns the number of channels required to meet the blocking probability, or `None`
+/// if the number of channels exceeds `channels_max` or the input is invalid.
+pub fn calculate_e1_channels_from(
+    traffic: f64,
+    blocking_probability: f64,
+    channels_max: u32,
+    estimate: Option<u32>,
+) -> Option<u32> {
+    try_calculate_e1_channels_from(traffic, blocking_probability, channels_max, estimate).ok()
+}
+
+/// Traffic below which the seeded search does not beat the single pass of
+/// `try_calculate_e1_channels`, so the estimate is not worth computing.
+const SEEDED_SEARCH_MIN_TRAFFIC: f64 = 200.0;
+
+/// Calculates the number of E1 voice channels required to satisfy a given blocking
+/// probability, starting the search from an estimate of the answer and validating the
+/// input.
+///
+/// The recurrence is seeded as in `try_erlang_b`: `1/B` at the traffic (or at the
+/// estimate, if lower) comes from the top-down expansion in about `10√A` terms, and the
+/// recurrence `1/B(n) = 1 + n/A × 1/B(n − 1)` carries it to the estimate. The search then
+/// walks up from there, or down with the reverse recurrence `1/B(n − 1) = (1/B(n) − 1) × A / n`.
+/// With an estimate within a few `√A` of the answer the work is `O(√A)` instead of the
+/// `O(A)` of the single pass. Without an estimate, traffic below 200 Erlangs goes straight
+/// to the single pass, which is cheaper there.
+///
+/// The result matches `try_calculate_e1_channels` except where the blocking equals the
+/// target to rounding error.
+///
+/// # Arguments
+/// * `traffic` - The traffic load in Erlangs (finite and non-negative).
+/// * `blocking_probability` - Desired blocking probability (strictly between 0 and 1).
+/// * `channels_max` - Maximum number of channels to search for (exclusive).
+/// * `estimate` - Starting channel count, or `None` to use `square_root_staffing_estimate`.
+///
+/// # Returns
+/// The number of channels required, `ErlangError::SearchLimitExceeded` with the best
+/// blocking reached if no count below `channels_max` suffices, or an input error.
+pub fn try_calculate_e1_channels_from(
+    traffic: f64,
+    blocking_probability: f64,
+    channels_max: u32,
+    estimate: Option<u32>,
+) -> Result<u32, ErlangError> {
+    validate_traffic(traffic)?;
+    validate_blocking_probability(blocking_probability)?;
+    if channels_max <= 1
+        || traffic == 0.0
+        || (estimate.is_none() && traffic < SEEDED_SEARCH_MIN_TRAFFIC)
+    {
+        return try_calculate_e1_channels(traffic, blocking_probability, channels_max);
+    }
 
-    while channels < channels_max {
-        let blocking = erlang_b(traffic, channels);
-        if blocking <= blocking_probability {
-            return Some(channels);
+    let estimate = estimate
+        .unwrap_or_else(|| square_root_staffing_estimate(traffic, blocking_probability))
+        .clamp(1, channels_max - 1);
+    let start = estimate.min(traffic.floor().min(u32::MAX as f64) as u32);
+    let mut inverse_b = inverse_erlang_b_expansion(traffic, start);
+    for n in start + 1..=estimate {
+        inverse_b = 1.0 + inverse_b * (n as f64 / traffic);
+    }
+
+    if inverse_b.is_infinite() {
+        // The estimate overshoots so far that the blocking underflows; nothing to walk back from.
+        return try_calculate_e1_channels(traffic, blocking_probability, channels_max);
+    }
+
+    if 1.0 / inverse_b <= blocking_probability {
+        let mut channels = estimate;
+        while channels > 1 {
+            let previous = (inverse_b - 1.0) * traffic / channels as f64;
+            if 1.0 / previous > blocking_probability {
+                break;
+            }
+            inverse_b = previous;
+            channels -= 1;
         }
-        channels += 1;
+        return Ok(channels);
     }
 
-    None
+    for channels in estimate + 1..channels_max {
+        inverse_b = 1.0 + inverse_b * (channels as f64 / traffic);
+        if 1.0 / inverse_b <= blocking_probability {
+            return Ok(channels);
+        }
+    }
+
+    Err(ErlangError::SearchLimitExceeded {
+        channels_max,
+        best_blocking: 1.0 / inverse_b,
+    })
+}
+
+/// Estimates the number of channels needed for a blocking probability using the
+/// square-root staffing rule `N ≈ A + β√A`.
+///
+/// `β` solves the Halfin-Whitt approximation `φ(β) / Φ(β) = B × √A` of the Erlang B
+/// formula, so the estimate is usually within a channel or two of the exact answer.
+///
+/// # Arguments
+/// * `traffic` - The traffic load in Erlangs.
+/// * `blocking_probability` - Desired blocking probability (between 0 and 1).
+///
+/// # Returns
+/// The estimated number of channels (at least 1).
+pub fn square_root_staffing_estimate(traffic: f64, blocking_probability: f64) -> u32 {
+    if !(traffic > 0.0 && blocking_probability > 0.0 && blocking_probability < 1.0) {
+        return 1;
+    }
+
+    let root = traffic.sqrt();
+    let log_target = -(blocking_probability * root).ln();
+
+    // ln(Φ/φ) increases with slope φ/Φ + β > 0, so Newton's method converges quickly;
+    // steps leaving the bracket fall back to bisection. A twentieth of a channel is enough.
+    let (mut low, mut high) = (-root.max(10.0), 10.0);
+    let mut beta = 0.0;
+    for _ in 0..100 {
+        let ratio = stats::normal_cdf_pdf_ratio(beta);
+        let excess = ratio.ln() - log_target;
+        if excess > 0.0 {
+            high = beta;
+        } else {
+            low = beta;
+        }
+        let mut next = beta - excess / (1.0 / ratio + beta);
+        if !(next > low && next < high) {
+            next = 0.5 * (low + high);
+        }
+        let step = next - beta;
+        beta = next;
+        if step.abs() * root < 0.05 || high - low < 1e-9 {
+            break;
+        }
+    }
+
+    (traffic + beta * root).round().max(1.0) as u32
 }
 
 /// Calculates the required number of E1 voice channels for a given number of users,
 /// average call duration, concurrent calls, and desired blocking probability.
 ///
 /// This is a helper function to convert user input into traffic (Erlangs) and then
-/// call `calculate_e1_channels` for the actual channel computation.
+/// call `calculate_e1_channels` for the actual channel computation. It is a thin
+/// wrapper around `try_required_e1_channels` that maps every error to `None`.
+///
+/// New code should prefer `profile::TrafficProfile`, which names the inputs and shows
+/// how the traffic is derived.
 ///
 /// # Arguments
 /// * `users` - Number of users.
 /// * `average_call_duration` - Average call duration in minutes.
-/// * `concurrent_calls` - Number of simultaneous calls.
+/// * `concurrent_calls` - Calls per user in the busy hour.
 /// * `blocking_probability` - Desired blocking probability.
 ///
 /// # Returns
@@ -68,8 +372,42 @@ pub fn required_e1_channels(
     concurrent_calls: u32,
     blocking_probability: f64,
 ) -> Option<u32> {
-    let traffic = (users as f64 * average_call_duration * concurrent_calls as f64) / 60.0;
-    calculate_e1_channels(traffic, blocking_probability, 10_000)
+    try_required_e1_channels(
+        users,
+        average_call_duration,
+        concurrent_calls,
+        blocking_probability,
+    )
+    .ok()
+}
+
+/// Calculates the required number of E1 voice channels for a given number of users,
+/// validating the input and the derived traffic.
+///
+/// The traffic is `users × concurrent_calls × average_call_duration / 60` Erlangs: the
+/// `concurrent_calls` argument is the number of calls each user makes in the busy hour.
+/// This is a wrapper around `profile::TrafficProfile` with every user active.
+///
+/// # Arguments
+/// * `users` - Number of users.
+/// * `average_call_duration` - Average call duration in minutes.
+/// * `concurrent_calls` - Calls per user in the busy hour.
+/// * `blocking_probability` - Desired blocking probability.
+///
+/// # Returns
+/// Number of required voice channels (searching up to 10,000), `ErlangError::NumericOverflow`
+/// if the derived traffic is not finite, `ErlangError::InvalidParameter` for a negative or
+/// non-finite duration, or any error from `try_calculate_e1_channels`.
+pub fn try_required_e1_channels(
+    users: u32,
+    average_call_duration: f64,
+    concurrent_calls: u32,
+    blocking_probability: f64,
+) -> Result<u32, ErlangError> {
+    TrafficProfile::new(users)
+        .calls_per_user(concurrent_calls as f64)
+        .holding_time(average_call_duration, TimeUnit::Minutes)
+        .calculate_channels(blocking_probability, 10_000)
 }
 
 #[cfg(test)]
@@ -94,6 +432,168 @@ mod tests {
         assert!(channels.unwrap() >= 1);
     }
 
+    #[test]
+    fn test_calculate_e1_channels_matches_erlang_b() {
+        for &traffic in &[0.5, 15.0, 120.0] {
+            let channels = calculate_e1_channels(traffic, 0.01, 1_000).unwrap();
+            assert!(erlang_b(traffic, channels) <= 0.01);
+            assert!(erlang_b(traffic, channels - 1) > 0.01);
+        }
+    }
+
+    #[test]
+    fn test_calculate_e1_channels_from() {
+        for &traffic in &[0.5, 15.0, 120.0, 2_500.0] {
+            let expected = calculate_e1_channels(traffic, 0.01, 10_000);
+            assert_eq!(
+                calculate_e1_channels_from(traffic, 0.01, 10_000, None),
+                expected
+            );
+            assert_eq!(
+                calculate_e1_channels_from(traffic, 0.01, 10_000, Some(1)),
+                expected
+            );
+            assert_eq!(
+                calculate_e1_channels_from(traffic, 0.01, 10_000, Some(9_000)),
+                expected
+            );
+        }
+        assert_eq!(calculate_e1_channels_from(120.0, 0.01, 100, None), None);
+
+        for &traffic in &[150.0, 1_000.0, 5_000.0, 40_000.0] {
+            for &blocking_probability in &[0.001, 0.01, 0.2] {
+                let expected = try_calculate_e1_channels(traffic, blocking_probability, 100_000);
+                for estimate in [None, Some(1), Some(expected.unwrap() - 3), Some(99_999)] {
+                    assert_eq!(
+                        try_calculate_e1_channels_from(
+                            traffic,
+                            blocking_probability,
+                            100_000,
+                            estimate
+                        ),
+                        expected
+                    );
+                }
+            }
+        }
+        assert!(matches!(
+            try_calculate_e1_channels_from(120.0, 0.01, 100, Some(50)),
+            Err(ErlangError::SearchLimitExceeded {
+                channels_max: 100,
+                ..
+            })
+        ));
+        assert_eq!(
+            try_calculate_e1_channels_from(-1.0, 0.01, 100, None),
+            Err(ErlangError::InvalidTraffic(-1.0))
+        );
+    }
+
+    #[test]
+    fn test_try_erlang_b() {
+        assert_eq!(try_erlang_b(0.0, 5), Ok(0.0));
+        assert_eq!(try_erlang_b(0.0, 0), Ok(1.0));
+        assert_eq!(
+            try_erlang_b(-1.0, 5),
+            Err(ErlangError::InvalidTraffic(-1.0))
+        );
+        assert!(erlang_b(-1.0, 5).is_nan());
+        assert_eq!(try_erlang_b(20.0, 10), Ok(erlang_b(20.0, 10)));
+    }
+
+    #[test]
+    fn test_erlang_b_high_precision_references() {
+        // Reference values computed with 60-digit arithmetic via the incomplete gamma function.
+        let references = [
+            (3.5, 2, 0.576_470_588_235_294_1),
+            (20.0, 30, 0.008_457_498_340_194_704),
+            (50.0, 10, 0.804_716_496_634_709_2),
+            (1_000.0, 1_000, 0.024_811_917_646_160_41),
+            (10_000.0, 10_000, 0.007_936_563_248_805_672),
+            (100_000.0, 100_000, 0.002_518_893_423_546_906_4),
+            (100_000.0, 101_000, 8.606_421_807_323_222e-6),
+            (400_000.0, 395_000, 0.012_691_631_430_573_842),
+            (500_000.0, 502_000, 1.039_230_218_897_817_7e-5),
+            (250_000.0, 260_000, 1.479_192_180_268_228_3e-89),
+            (1_000_000.0, 500_000, 0.500_000_999_992_000_1),
+            (1_000_000.0, 999_000, 0.001_524_480_765_313_65),
+            (1_000_000.0, 1_000_000, 7.974_603_068_555_61e-4),
+            (1_000_000.0, 1_005_000, 1.514_158_544_828_019_1e-9),
+            (10_000_000.0, 10_000_000, 2.522_708_159_199_475e-4),
+            (1e9, 1_000_000_000, 2.523_090_081_205_638_6e-5),
+        ];
+        for (traffic, channels, expected) in references {
+            let blocking = erlang_b(traffic, channels);
+            let error = ((blocking - expected) / expected).abs();
+            assert!(error < 1e-12, "B({traffic}, {channels}) = {blocking}");
+        }
+        assert_eq!(erlang_b(0.5, 1_000_000), 0.0);
+    }
+
+    #[test]
+    fn test_erlang_b_jagerman() {
+        for (traffic, tolerance) in [(10_000.0, 1e-4), (1_000_000.0, 1e-6)] {
+            for beta in [-3.0, -1.0, 0.0, 1.0, 2.0] {
+                let channels = (traffic + beta * f64::sqrt(traffic)) as u32;
+                let exact = erlang_b(traffic, channels);
+                let approximate = erlang_b_jagerman(traffic, channels);
+                assert!(((approximate - exact) / exact).abs() < tolerance);
+            }
+        }
+        assert_eq!(erlang_b_jagerman(100.0, 10_000), 0.0);
+        assert!(erlang_b_jagerman(-1.0, 10).is_nan());
+    }
+
+    #[test]
+    fn test_try_calculate_e1_channels() {
+        assert_eq!(try_calculate_e1_channels(15.0, 0.05, 100), Ok(20));
+        assert_eq!(
+            try_calculate_e1_channels(15.0, 1.5, 100),
+            Err(ErlangError::InvalidBlockingProbability(1.5))
+        );
+        assert!(matches!(
+            try_calculate_e1_channels(f64::NAN, 0.05, 100),
+            Err(ErlangError::InvalidTraffic(traffic)) if traffic.is_nan()
+        ));
+        match try_calculate_e1_channels(15.0, 0.05, 10) {
+            Err(ErlangError::SearchLimitExceeded {
+                channels_max,
+                best_blocking,
+            }) => {
+                assert_eq!(channels_max, 10);
+                assert_eq!(best_blocking, erlang_b(15.0, 9));
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_try_required_e1_channels() {
+        assert_eq!(
+            try_required_e1_channels(u32::MAX, f64::MAX, 10, 0.05),
+            Err(ErlangError::NumericOverflow)
+        );
+        assert_eq!(
+            try_required_e1_channels(100, 3.0, 10, 0.05).ok(),
+            required_e1_channels(100, 3.0, 10, 0.05)
+        );
+    }
+
+    #[test]
+    fn test_square_root_staffing_estimate() {
+        for &traffic in &[1.0, 100.0, 2_500.0, 100_000.0] {
+            for &blocking_probability in &[0.001, 0.01, 0.1, 0.5] {
+                let estimate = square_root_staffing_estimate(traffic, blocking_probability);
+                let exact =
+                    calculate_e1_channels(traffic, blocking_probability, 1_000_000).unwrap();
+                assert!(
+                    estimate.abs_diff(exact) <= 2,
+                    "{traffic} Erl at {blocking_probability}: {estimate} vs {exact}"
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_required_e1_channels() {
         let users = 100;
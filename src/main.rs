@@ -0,0 +1,639 @@
+// Command-line tool for trunk dimensioning with the Erlang E1 library.
+
+use erlang_e1::batch::{dimension_routes, result_fields, RouteResult, RowError, RESULT_COLUMNS};
+use erlang_e1::inverse::max_traffic;
+use erlang_e1::profile::{TimeUnit, TrafficProfile};
+use erlang_e1::table::{erlang_b_table, render_table, TableConfig, TableFormat};
+use erlang_e1::trunk::{spans_for_channels, TrunkType};
+use erlang_e1::{try_calculate_e1_channels, try_erlang_b};
+use std::io::{self, BufRead};
+use std::process::ExitCode;
+use std::str::FromStr;
+
+/// Maximum channel count searched when `--max` is not given.
+const DEFAULT_CHANNELS_MAX: u32 = 10_000;
+
+const USAGE: &str = "\
+Usage: erlang_e1 <command> [--flag value ...] [--format text|csv|json|markdown|html]
+
+Commands:
+  blocking  --traffic A --channels N
+            Erlang B blocking probability.
+  channels  --traffic A --gos B [--max N]
+            --users U --duration MINUTES --calls C --gos B
+            Channels needed to meet a blocking probability.
+  traffic   --channels N --gos B
+            Maximum traffic a channel group carries at a blocking probability.
+  spans     --channels N [--trunk TYPE]
+            --traffic A --gos B [--max N] [--trunk TYPE]
+            Spans needed to carry the channels; TYPE is e1-pri (default), e1-cas,
+            t1-pri, t1-cas or j1.
+  table     [--from N] [--to N] [--gos B,B,...] [--precision DIGITS]
+            Erlang B table of the maximum traffic per channel count and blocking
+            probability (defaults: 1 to 100 channels at 0.001, 0.005, 0.01, 0.02
+            and 0.05, two decimal places).
+  batch     [--max N]
+            Dimension every route of a CSV scenario file read from stdin. The header
+            names the columns: gos, plus traffic or users, holding_time (seconds) and
+            bhca with completion_ratio or calls_per_user; optionally route, trunk,
+            growth, periods, active_percent and max. Invalid rows are reported in the error column.
+  help      Show this message.
+
+Without input flags, blocking, channels, traffic and spans read one calculation per
+line from stdin, with the values in the order shown above separated by spaces or
+commas, e.g. `20 30` for blocking or `100 3 2 0.01` for channels from users. Blank
+lines and lines starting with # are skipped.
+";
+
+/// Output format selected with `--format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Csv,
+    Json,
+    Markdown,
+    Html,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "text" => Ok(Format::Text),
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            "markdown" => Ok(Format::Markdown),
+            "html" => Ok(Format::Html),
+            _ => Err(format!(
+                "unknown format `{name}` (expected text, csv, json, markdown or html)"
+            )),
+        }
+    }
+}
+
+/// A single output value.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(f64),
+    Text(String),
+    /// A number that does not apply: empty in tables, `null` in JSON.
+    Missing,
+}
+
+impl Value {
+    fn render(&self) -> String {
+        match self {
+            Value::Number(number) => number.to_string(),
+            Value::Text(text) => text.clone(),
+            Value::Missing => String::new(),
+        }
+    }
+}
+
+/// One output row as named values, in column order.
+type Record = Vec<(String, Value)>;
+
+fn number(name: &str, value: impl Into<f64>) -> (String, Value) {
+    (name.to_string(), Value::Number(value.into()))
+}
+
+fn text(name: &str, value: impl ToString) -> (String, Value) {
+    (name.to_string(), Value::Text(value.to_string()))
+}
+
+/// `--name value` (or `--name=value`) pairs in command-line order.
+struct Flags {
+    pairs: Vec<(String, String)>,
+}
+
+impl Flags {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut pairs = Vec::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let name = arg
+                .strip_prefix("--")
+                .ok_or_else(|| format!("unexpected argument `{arg}`"))?;
+            let (name, value) = match name.split_once('=') {
+                Some((name, value)) => (name.to_string(), value.to_string()),
+                None => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("missing value for --{name}"))?;
+                    (name.to_string(), value.clone())
+                }
+            };
+            pairs.push((name, value));
+        }
+        Ok(Flags { pairs })
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .rev()
+            .find(|(flag, _)| flag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn has_any(&self, names: &[&str]) -> bool {
+        names.iter().any(|name| self.get(name).is_some())
+    }
+
+    fn optional<T: FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        self.get(name).map(|value| parse(name, value)).transpose()
+    }
+
+    fn required<T: FromStr>(&self, name: &str) -> Result<T, String> {
+        self.optional(name)?
+            .ok_or_else(|| format!("missing --{name}"))
+    }
+
+    fn check_allowed(&self, command: &str, allowed: &[&str]) -> Result<(), String> {
+        match self
+            .pairs
+            .iter()
+            .find(|(flag, _)| flag != "format" && !allowed.contains(&flag.as_str()))
+        {
+            Some((flag, _)) => Err(format!("unknown flag --{flag} for `{command}`")),
+            None => Ok(()),
+        }
+    }
+}
+
+fn parse<T: FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value `{value}` for {name}"))
+}
+
+/// Reads calculation inputs from stdin: one line per calculation, values separated by
+/// spaces or commas.
+fn read_rows(input: &mut dyn BufRead) -> Result<Vec<(usize, Vec<String>)>, String> {
+    let mut rows = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let line = line.map_err(|error| format!("failed to read stdin: {error}"))?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|field| !field.is_empty())
+            .map(str::to_string)
+            .collect();
+        rows.push((index + 1, fields));
+    }
+    Ok(rows)
+}
+
+/// Runs `calculate` for every stdin row, prefixing errors with the line number.
+fn from_rows(
+    input: &mut dyn BufRead,
+    calculate: impl Fn(&[String]) -> Result<Record, String>,
+) -> Result<Vec<Record>, String> {
+    read_rows(input)?
+        .iter()
+        .map(|(line, fields)| calculate(fields).map_err(|error| format!("line {line}: {error}")))
+        .collect()
+}
+
+fn blocking_record(traffic: f64, channels: u32) -> Result<Record, String> {
+    let blocking = try_erlang_b(traffic, channels).map_err(|error| error.to_string())?;
+    Ok(vec![
+        number("traffic", traffic),
+        number("channels", channels),
+        number("blocking", blocking),
+    ])
+}
+
+fn blocking(flags: &Flags, input: &mut dyn BufRead) -> Result<Vec<Record>, String> {
+    flags.check_allowed("blocking", &["traffic", "channels"])?;
+    if flags.has_any(&["traffic", "channels"]) {
+        return Ok(vec![blocking_record(
+            flags.required("traffic")?,
+            flags.required("channels")?,
+        )?]);
+    }
+    from_rows(input, |fields| match fields {
+        [traffic, channels] => {
+            blocking_record(parse("traffic", traffic)?, parse("channels", channels)?)
+        }
+        _ => Err("expected `traffic channels`".to_string()),
+    })
+}
+
+fn channels_record(traffic: f64, gos: f64, channels_max: u32) -> Result<Record, String> {
+    let channels =
+        try_calculate_e1_channels(traffic, gos, channels_max).map_err(|error| error.to_string())?;
+    Ok(vec![
+        number("traffic", traffic),
+        number("gos", gos),
+        number("channels", channels),
+    ])
+}
+
+fn users_record(
+    users: u32,
+    duration: f64,
+    calls: u32,
+    gos: f64,
+    channels_max: u32,
+) -> Result<Record, String> {
+    let profile = TrafficProfile::new(users)
+        .calls_per_user(calls as f64)
+        .holding_time(duration, TimeUnit::Minutes);
+    let traffic = profile.traffic().map_err(|error| error.to_string())?;
+    let channels =
+        try_calculate_e1_channels(traffic, gos, channels_max).map_err(|error| error.to_string())?;
+    Ok(vec![
+        number("traffic", traffic),
+        number("gos", gos),
+        number("channels", channels),
+    ])
+}
+
+fn channels(flags: &Flags, input: &mut dyn BufRead) -> Result<Vec<Record>, String> {
+    flags.check_allowed(
+        "channels",
+        &["traffic", "gos", "max", "users", "duration", "calls"],
+    )?;
+    let channels_max = flags.optional("max")?.unwrap_or(DEFAULT_CHANNELS_MAX);
+    if flags.has_any(&["users", "duration", "calls"]) {
+        return Ok(vec![users_record(
+            flags.required("users")?,
+            flags.required("duration")?,
+            flags.required("calls")?,
+            flags.required("gos")?,
+            channels_max,
+        )?]);
+    }
+    if flags.has_any(&["traffic", "gos"]) {
+        return Ok(vec![channels_record(
+            flags.required("traffic")?,
+            flags.required("gos")?,
+            channels_max,
+        )?]);
+    }
+    from_rows(input, |fields| match fields {
+        [traffic, gos] => {
+            channels_record(parse("traffic", traffic)?, parse("gos", gos)?, channels_max)
+        }
+        [users, duration, calls, gos] => users_record(
+            parse("users", users)?,
+            parse("duration", duration)?,
+            parse("calls", calls)?,
+            parse("gos", gos)?,
+            channels_max,
+        ),
+        _ => Err("expected `traffic gos` or `users duration calls gos`".to_string()),
+    })
+}
+
+fn traffic_record(channels: u32, gos: f64) -> Result<Record, String> {
+    let inverse = max_traffic(channels, gos)
+        .ok_or("channels must be positive and gos between 0 and 1".to_string())?;
+    Ok(vec![
+        number("channels", channels),
+        number("gos", gos),
+        number("traffic", inverse.traffic),
+    ])
+}
+
+fn traffic(flags: &Flags, input: &mut dyn BufRead) -> Result<Vec<Record>, String> {
+    flags.check_allowed("traffic", &["channels", "gos"])?;
+    if flags.has_any(&["channels", "gos"]) {
+        return Ok(vec![traffic_record(
+            flags.required("channels")?,
+            flags.required("gos")?,
+        )?]);
+    }
+    from_rows(input, |fields| match fields {
+        [channels, gos] => traffic_record(parse("channels", channels)?, parse("gos", gos)?),
+        _ => Err("expected `channels gos`".to_string()),
+    })
+}
+
+fn spans_record(channels: u32, trunk_type: TrunkType) -> Result<Record, String> {
+    let plan = spans_for_channels(channels, trunk_type).map_err(|error| error.to_string())?;
+    Ok(vec![
+        text("trunk", plan.trunk_type),
+        number("channels", plan.channels),
+        number("spans", plan.spans),
+        number("bearer_capacity", plan.bearer_capacity),
+        number("spare_channels", plan.spare_channels),
+    ])
+}
+
+fn spans_from_traffic(
+    traffic: f64,
+    gos: f64,
+    channels_max: u32,
+    trunk_type: TrunkType,
+) -> Result<Record, String> {
+    let channels =
+        try_calculate_e1_channels(traffic, gos, channels_max).map_err(|error| error.to_string())?;
+    spans_record(channels, trunk_type)
+}
+
+fn spans(flags: &Flags, input: &mut dyn BufRead) -> Result<Vec<Record>, String> {
+    flags.check_allowed("spans", &["channels", "traffic", "gos", "max", "trunk"])?;
+    let channels_max = flags.optional("max")?.unwrap_or(DEFAULT_CHANNELS_MAX);
+    let trunk_type = match flags.get("trunk") {
+        Some(name) => name
+            .parse()
+            .map_err(|error: erlang_e1::ErlangError| error.to_string())?,
+        None => TrunkType::E1Pri,
+    };
+    if flags.get("channels").is_some() {
+        return Ok(vec![spans_record(flags.required("channels")?, trunk_type)?]);
+    }
+    if flags.has_any(&["traffic", "gos"]) {
+        return Ok(vec![spans_from_traffic(
+            flags.required("traffic")?,
+            flags.required("gos")?,
+            channels_max,
+            trunk_type,
+        )?]);
+    }
+    from_rows(input, |fields| match fields {
+        [channels] => spans_record(parse("channels", channels)?, trunk_type),
+        [traffic, gos] => spans_from_traffic(
+            parse("traffic", traffic)?,
+            parse("gos", gos)?,
+            channels_max,
+            trunk_type,
+        ),
+        _ => Err("expected `channels` or `traffic gos`".to_string()),
+    })
+}
+
+fn batch_record(result: &Result<RouteResult, RowError>) -> Record {
+    RESULT_COLUMNS
+        .iter()
+        .zip(result_fields(result))
+        .map(|(&name, field)| {
+            if matches!(name, "route" | "trunk" | "error") {
+                text(name, field)
+            } else if field.is_empty() {
+                (name.to_string(), Value::Missing)
+            } else {
+                number(name, field.parse::<f64>().unwrap_or(f64::NAN))
+            }
+        })
+        .collect()
+}
+
+fn batch(flags: &Flags, input: &mut dyn BufRead) -> Result<Vec<Record>, String> {
+    flags.check_allowed("batch", &["max"])?;
+    let channels_max = flags.optional("max")?.unwrap_or(DEFAULT_CHANNELS_MAX);
+    let mut scenario = String::new();
+    input
+        .read_to_string(&mut scenario)
+        .map_err(|error| format!("failed to read stdin: {error}"))?;
+    let results = dimension_routes(&scenario, channels_max).map_err(|error| error.to_string())?;
+    Ok(results.iter().map(batch_record).collect())
+}
+
+fn table(flags: &Flags, format: Format) -> Result<String, String> {
+    flags.check_allowed("table", &["from", "to", "gos", "precision"])?;
+    let defaults = TableConfig::default();
+    let mut config = TableConfig {
+        channels_from: flags.optional("from")?.unwrap_or(defaults.channels_from),
+        channels_to: flags.optional("to")?.unwrap_or(defaults.channels_to),
+        precision: flags.optional("precision")?.unwrap_or(defaults.precision),
+        ..defaults
+    };
+    if let Some(gos) = flags.get("gos") {
+        config.blocking_probabilities = gos
+            .split(',')
+            .map(|value| parse("gos", value.trim()))
+            .collect::<Result<_, _>>()?;
+    }
+    let table = erlang_b_table(&config).map_err(|error| error.to_string())?;
+
+    let table_format = match format {
+        Format::Text => TableFormat::Text,
+        Format::Csv => TableFormat::Csv,
+        Format::Markdown => TableFormat::Markdown,
+        Format::Html => TableFormat::Html,
+        Format::Json => {
+            let scale = 10f64.powi(table.precision as i32);
+            let headings = table.headings();
+            let records: Vec<Record> =
+                table
+                    .rows
+                    .iter()
+                    .map(|row| {
+                        std::iter::once(number(&headings[0], row.channels))
+                            .chain(row.traffic.iter().zip(&headings[1..]).map(
+                                |(traffic, heading)| {
+                                    number(heading, (traffic * scale).round() / scale)
+                                },
+                            ))
+                            .collect()
+                    })
+                    .collect();
+            return Ok(render(&records, format));
+        }
+    };
+    Ok(table.render(table_format))
+}
+
+fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn render(records: &[Record], format: Format) -> String {
+    let Some(first) = records.first() else {
+        return match format {
+            Format::Json => "[]\n".to_string(),
+            Format::Text | Format::Csv | Format::Markdown | Format::Html => String::new(),
+        };
+    };
+    let table_format = match format {
+        Format::Text => TableFormat::Text,
+        Format::Csv => TableFormat::Csv,
+        Format::Markdown => TableFormat::Markdown,
+        Format::Html => TableFormat::Html,
+        Format::Json => {
+            let objects: Vec<String> = records
+                .iter()
+                .map(|record| {
+                    let fields: Vec<String> = record
+                        .iter()
+                        .map(|(name, value)| {
+                            let value = match value {
+                                Value::Number(number) if number.is_finite() => number.to_string(),
+                                Value::Number(_) | Value::Missing => "null".to_string(),
+                                Value::Text(text) => escape_json(text),
+                            };
+                            format!("{}: {value}", escape_json(name))
+                        })
+                        .collect();
+                    format!("  {{{}}}", fields.join(", "))
+                })
+                .collect();
+            return format!("[\n{}\n]\n", objects.join(",\n"));
+        }
+    };
+    let headings: Vec<String> = first.iter().map(|(name, _)| name.clone()).collect();
+    let cells: Vec<Vec<String>> = records
+        .iter()
+        .map(|record| record.iter().map(|(_, value)| value.render()).collect())
+        .collect();
+    render_table(&headings, &cells, table_format)
+}
+
+/// Runs a command line (without the program name), reading stdin rows from `input`.
+fn run(args: &[String], input: &mut dyn BufRead) -> Result<String, String> {
+    let Some(command) = args.first() else {
+        return Err("missing command".to_string());
+    };
+    if matches!(command.as_str(), "help" | "--help" | "-h") {
+        return Ok(USAGE.to_string());
+    }
+
+    let flags = Flags::parse(&args[1..])?;
+    let format = flags.optional("format")?.unwrap_or(Format::Text);
+    let records = match command.as_str() {
+        "blocking" => blocking(&flags, input)?,
+        "channels" => channels(&flags, input)?,
+        "traffic" => traffic(&flags, input)?,
+        "spans" => spans(&flags, input)?,
+        "table" => return table(&flags, format),
+        "batch" => batch(&flags, input)?,
+        _ => return Err(format!("unknown command `{command}`")),
+    };
+    Ok(render(&records, format))
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args, &mut io::stdin().lock()) {
+        Ok(output) => {
+            print!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {error}");
+            eprintln!("Run `erlang_e1 help` for usage.");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_with(command: &str, stdin: &str) -> Result<String, String> {
+        let args: Vec<String> = command.split_whitespace().map(str::to_string).collect();
+        run(&args, &mut stdin.as_bytes())
+    }
+
+    #[test]
+    fn test_flags() {
+        assert_eq!(
+            run_with("channels --traffic 15 --gos=0.05 --format csv", ""),
+            Ok("traffic,gos,channels\n15,0.05,20\n".to_string())
+        );
+        assert_eq!(
+            run_with(
+                "channels --users 100 --duration 3 --calls 2 --gos 0.01 --format csv",
+                ""
+            ),
+            Ok("traffic,gos,channels\n10,0.01,18\n".to_string())
+        );
+        // The channel search limit applies to user inputs too.
+        assert!(run_with(
+            "channels --users 100 --duration 3 --calls 2 --gos 0.01 --max 10",
+            ""
+        )
+        .is_err());
+        assert!(run_with("blocking --traffic 20", "").is_err());
+        assert!(run_with("blocking --traffic 20 --channels 10 --gos 0.1", "").is_err());
+        assert!(run_with("blocking --traffic", "").is_err());
+        assert!(run_with("dial", "").is_err());
+    }
+
+    #[test]
+    fn test_stdin_rows() {
+        let output = run_with(
+            "blocking --format csv",
+            "# traffic channels\n20 30\n\n10,10\n",
+        );
+        let lines: Vec<&str> = output.as_ref().unwrap().lines().collect();
+        assert_eq!(lines[0], "traffic,channels,blocking");
+        assert!(lines[1].starts_with("20,30,0.0084574"));
+        assert!(lines[2].starts_with("10,10,0.2145"));
+
+        assert_eq!(
+            run_with("traffic", "10 0.01\n10\n"),
+            Err("line 2: expected `channels gos`".to_string())
+        );
+    }
+
+    #[test]
+    fn test_spans_and_table() {
+        assert_eq!(
+            run_with("spans --traffic 50 --gos 0.01 --format json", ""),
+            Ok(
+                "[\n  {\"trunk\": \"e1-pri\", \"channels\": 64, \"spans\": 3, \
+                \"bearer_capacity\": 90, \"spare_channels\": 26}\n]\n"
+                    .to_string()
+            )
+        );
+        assert!(run_with("spans --channels 10 --trunk e2", "").is_err());
+
+        let table = run_with("table --from 10 --to 10 --gos 0.01,0.05", "").unwrap();
+        assert_eq!(table, " N    1%    5%\n10  4.46  6.22\n");
+        let table = run_with("table --to 1 --gos 0.01 --precision 4 --format json", "");
+        assert_eq!(
+            table,
+            Ok("[\n  {\"N\": 1, \"1%\": 0.0101}\n]\n".to_string())
+        );
+        let blocking = run_with("blocking --traffic 1 --channels 1 --format markdown", "");
+        assert_eq!(
+            blocking,
+            Ok(
+                "| traffic | channels | blocking |\n|---:|---:|---:|\n| 1 | 1 | 0.5 |\n"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_batch() {
+        let scenario = "route,traffic,gos,trunk\na,50,0.01,e1-pri\nb,20,0.01,e2\n";
+        let output = run_with("batch --format csv", scenario).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[0],
+            "line,route,traffic,trunk,channels,spans,installed_channels,blocking,utilization,error"
+        );
+        assert!(lines[1].starts_with("2,a,50,e1-pri,64,3,90,"));
+        assert!(lines[2].starts_with("3,b,,,,,,,,\"invalid parameter: unknown trunk type"));
+        let json = run_with("batch --format json", scenario).unwrap();
+        assert!(json.contains(
+            "\"route\": \"a\", \"traffic\": 50, \"trunk\": \"e1-pri\", \"channels\": 64"
+        ));
+        assert!(json.contains(
+            "\"line\": 3, \"route\": \"b\", \"traffic\": null, \"trunk\": \"\", \"channels\": null"
+        ));
+        assert!(run_with("batch", "route,traffic\na,10\n").is_err());
+    }
+}
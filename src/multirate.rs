@@ -0,0 +1,235 @@
+// Multi-rate loss systems using the Kaufman-Roberts recursion.
+
+use crate::error::{validate_blocking_probability, validate_traffic};
+use crate::ErlangError;
+
+/// Rescaling threshold for the unnormalised occupancy distribution.
+const RESCALE_THRESHOLD: f64 = 1e200;
+
+/// A class of calls sharing a link, each call occupying a fixed number of channel units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrafficClass {
+    /// Offered traffic of the class in Erlangs (calls, not channel units).
+    pub traffic: f64,
+    /// Channel units occupied by each call, e.g. 1 for voice and 6 for video.
+    pub bandwidth: u32,
+}
+
+/// A traffic class with its grade-of-service target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClassTarget {
+    /// The traffic class.
+    pub class: TrafficClass,
+    /// Maximum acceptable blocking probability for the class (between 0 and 1).
+    pub blocking_probability: f64,
+}
+
+/// Incrementally evaluated Kaufman-Roberts occupancy distribution.
+struct Occupancy<'a> {
+    classes: &'a [TrafficClass],
+    /// Unnormalised probabilities `q(j)` of `j` busy units.
+    states: Vec<f64>,
+    total: f64,
+}
+
+impl<'a> Occupancy<'a> {
+    fn new(classes: &'a [TrafficClass]) -> Result<Self, ErlangError> {
+        for class in classes {
+            validate_traffic(class.traffic)?;
+            if class.bandwidth == 0 {
+                return Err(ErlangError::InvalidParameter(
+                    "class bandwidth must be at least one channel unit",
+                ));
+            }
+        }
+
+        Ok(Occupancy {
+            classes,
+            states: vec![1.0],
+            total: 1.0,
+        })
+    }
+
+    fn capacity(&self) -> u32 {
+        self.states.len() as u32 - 1
+    }
+
+    /// Extends the distribution by one channel unit: `j × q(j) = Σ a_k × b_k × q(j − b_k)`.
+    fn grow(&mut self) {
+        let j = self.states.len();
+        let next = self
+            .classes
+            .iter()
+            .filter(|class| class.bandwidth as usize <= j)
+            .map(|class| {
+                class.traffic * class.bandwidth as f64 * self.states[j - class.bandwidth as usize]
+            })
+            .sum::<f64>()
+            / j as f64;
+        self.states.push(next);
+        self.total += next;
+
+        if next > RESCALE_THRESHOLD {
+            self.states.iter_mut().for_each(|state| *state /= next);
+            self.total /= next;
+        }
+    }
+
+    /// Blocking of a class: the probability that fewer than `bandwidth` units are free.
+    fn blocking(&self, class: &TrafficClass) -> f64 {
+        let capacity = self.capacity() as usize;
+        let bandwidth = class.bandwidth as usize;
+        if bandwidth > capacity {
+            return 1.0;
+        }
+        self.states[capacity + 1 - bandwidth..].iter().sum::<f64>() / self.total
+    }
+}
+
+/// Calculates the blocking probability of each traffic class on a link using the
+/// Kaufman-Roberts recursion.
+///
+/// # Arguments
+/// * `classes` - The traffic classes sharing the link.
+/// * `capacity` - Link capacity in channel units.
+///
+/// # Returns
+/// The blocking probability of each class in input order, or an error if a class has
+/// invalid traffic or zero bandwidth.
+pub fn kaufman_roberts(classes: &[TrafficClass], capacity: u32) -> Result<Vec<f64>, ErlangError> {
+    let mut occupancy = Occupancy::new(classes)?;
+    while occupancy.capacity() < capacity {
+        occupancy.grow();
+    }
+
+    Ok(classes
+        .iter()
+        .map(|class| occupancy.blocking(class))
+        .collect())
+}
+
+/// Calculates the minimum link capacity at which every class meets its blocking target.
+///
+/// The occupancy distribution does not depend on the capacity apart from its
+/// normalisation, so it is built once while the capacity grows.
+///
+/// # Arguments
+/// * `targets` - The traffic classes with their blocking targets.
+/// * `capacity_max` - Maximum capacity in channel units to search for.
+///
+/// # Returns
+/// The capacity in channel units, `ErlangError::SearchLimitExceeded` with the worst class
+/// blocking at the limit, or an input error.
+pub fn calculate_capacity(targets: &[ClassTarget], capacity_max: u32) -> Result<u32, ErlangError> {
+    for target in targets {
+        validate_blocking_probability(target.blocking_probability)?;
+    }
+    let classes: Vec<TrafficClass> = targets.iter().map(|target| target.class).collect();
+    let mut occupancy = Occupancy::new(&classes)?;
+
+    let mut worst_blocking = 1.0;
+    while occupancy.capacity() < capacity_max {
+        let meets_targets = targets
+            .iter()
+            .all(|target| occupancy.blocking(&target.class) <= target.blocking_probability);
+        if meets_targets {
+            return Ok(occupancy.capacity());
+        }
+
+        worst_blocking = targets
+            .iter()
+            .map(|target| occupancy.blocking(&target.class))
+            .fold(0.0, f64::max);
+        occupancy.grow();
+    }
+
+    Err(ErlangError::SearchLimitExceeded {
+        channels_max: capacity_max,
+        best_blocking: worst_blocking,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{calculate_e1_channels, erlang_b};
+
+    #[test]
+    fn test_single_class_is_erlang_b() {
+        let classes = [TrafficClass {
+            traffic: 20.0,
+            bandwidth: 1,
+        }];
+        let blocking = kaufman_roberts(&classes, 25).unwrap();
+        assert!((blocking[0] - erlang_b(20.0, 25)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_kaufman_roberts() {
+        // Voice at 1 unit and video at 4 units on a 10-unit link.
+        let classes = [
+            TrafficClass {
+                traffic: 2.0,
+                bandwidth: 1,
+            },
+            TrafficClass {
+                traffic: 1.0,
+                bandwidth: 4,
+            },
+        ];
+        let blocking = kaufman_roberts(&classes, 10).unwrap();
+        assert!((blocking[0] - 0.063_160).abs() < 1e-6);
+        assert!((blocking[1] - 0.283_510).abs() < 1e-6);
+
+        let zero = [TrafficClass {
+            traffic: 1.0,
+            bandwidth: 0,
+        }];
+        assert!(matches!(
+            kaufman_roberts(&zero, 10),
+            Err(ErlangError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_calculate_capacity() {
+        let targets = [
+            ClassTarget {
+                class: TrafficClass {
+                    traffic: 30.0,
+                    bandwidth: 1,
+                },
+                blocking_probability: 0.01,
+            },
+            ClassTarget {
+                class: TrafficClass {
+                    traffic: 2.0,
+                    bandwidth: 6,
+                },
+                blocking_probability: 0.05,
+            },
+        ];
+        let capacity = calculate_capacity(&targets, 1_000).unwrap();
+        let classes = [targets[0].class, targets[1].class];
+        let blocking = kaufman_roberts(&classes, capacity).unwrap();
+        assert!(blocking[0] <= 0.01 && blocking[1] <= 0.05);
+        let below = kaufman_roberts(&classes, capacity - 1).unwrap();
+        assert!(below[0] > 0.01 || below[1] > 0.05);
+        assert!(capacity > calculate_e1_channels(30.0, 0.01, 1_000).unwrap());
+
+        assert!(matches!(
+            calculate_capacity(&targets, 10),
+            Err(ErlangError::SearchLimitExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn test_large_capacity_rescales() {
+        let classes = [TrafficClass {
+            traffic: 2_000.0,
+            bandwidth: 2,
+        }];
+        let blocking = kaufman_roberts(&classes, 4_000).unwrap();
+        assert!(blocking[0].is_finite() && blocking[0] > 0.0 && blocking[0] < 0.1);
+    }
+}
@@ -0,0 +1,526 @@
+// Network-wide dimensioning with the Erlang fixed-point (reduced load) approximation.
+
+use crate::error::{validate_blocking_probability, validate_traffic};
+use crate::{erlang_b, try_calculate_e1_channels, ErlangError};
+use std::collections::{BTreeMap, VecDeque};
+
+/// Convergence threshold for link blocking probabilities in the fixed-point iteration.
+const BLOCKING_TOLERANCE: f64 = 1e-10;
+
+/// Maximum number of fixed-point iterations.
+const MAX_ITERATIONS: u32 = 1_000;
+
+/// Maximum number of resizing rounds in `optimize_channels`.
+const MAX_SIZING_ROUNDS: u32 = 100;
+
+/// A bidirectional trunk group between two switches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    /// Index of one end node.
+    pub from: usize,
+    /// Index of the other end node.
+    pub to: usize,
+    /// Number of channels on the link.
+    pub channels: u32,
+}
+
+/// A fixed path between two switches as a sequence of links.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    /// Index of the origin node.
+    pub origin: usize,
+    /// Index of the destination node.
+    pub destination: usize,
+    /// Indices of the links traversed, in order.
+    pub links: Vec<usize>,
+}
+
+/// Switches, the links between them and the routes calls follow.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Network {
+    nodes: Vec<String>,
+    links: Vec<Link>,
+    routes: Vec<Route>,
+}
+
+impl Network {
+    /// Creates an empty network.
+    pub fn new() -> Self {
+        Network::default()
+    }
+
+    /// Adds a switch and returns its index.
+    pub fn add_node(&mut self, name: &str) -> usize {
+        self.nodes.push(name.to_string());
+        self.nodes.len() - 1
+    }
+
+    /// Adds a link between two nodes and returns its index.
+    ///
+    /// # Returns
+    /// The link index, or `ErlangError::InvalidParameter` if a node does not exist or
+    /// both ends are the same node.
+    pub fn add_link(
+        &mut self,
+        from: usize,
+        to: usize,
+        channels: u32,
+    ) -> Result<usize, ErlangError> {
+        if from >= self.nodes.len() || to >= self.nodes.len() || from == to {
+            return Err(ErlangError::InvalidParameter(
+                "link must join two different existing nodes",
+            ));
+        }
+        self.links.push(Link { from, to, channels });
+        Ok(self.links.len() - 1)
+    }
+
+    /// Adds an explicit route following `links` from `origin`. Demands between the route's
+    /// end nodes use it instead of the shortest path.
+    ///
+    /// # Returns
+    /// The route index, or `ErlangError::InvalidParameter` if the links do not form a
+    /// connected path starting at `origin`.
+    pub fn add_route(&mut self, origin: usize, links: Vec<usize>) -> Result<usize, ErlangError> {
+        let mut node = origin;
+        for &link in &links {
+            let link = self.links.get(link).ok_or(ErlangError::InvalidParameter(
+                "route refers to an unknown link",
+            ))?;
+            node = match node {
+                n if n == link.from => link.to,
+                n if n == link.to => link.from,
+                _ => {
+                    return Err(ErlangError::InvalidParameter(
+                        "route links must form a connected path",
+                    ))
+                }
+            };
+        }
+        if links.is_empty() || origin >= self.nodes.len() {
+            return Err(ErlangError::InvalidParameter(
+                "route must start at an existing node and use at least one link",
+            ));
+        }
+
+        self.routes.push(Route {
+            origin,
+            destination: node,
+            links,
+        });
+        Ok(self.routes.len() - 1)
+    }
+
+    /// The node names, indexed by node.
+    pub fn nodes(&self) -> &[String] {
+        &self.nodes
+    }
+
+    /// The links, indexed by link.
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    /// The explicit routes, indexed by route.
+    pub fn routes(&self) -> &[Route] {
+        &self.routes
+    }
+
+    /// Sets the channel count of every link, e.g. from `optimize_channels`.
+    ///
+    /// # Panics
+    /// Panics if `channels` does not have one entry per link.
+    pub fn set_channels(&mut self, channels: &[u32]) {
+        assert_eq!(
+            channels.len(),
+            self.links.len(),
+            "one channel count per link"
+        );
+        for (link, &count) in self.links.iter_mut().zip(channels) {
+            link.channels = count;
+        }
+    }
+
+    /// Finds the path between two nodes with the fewest links (breadth-first search).
+    pub fn shortest_path(&self, origin: usize, destination: usize) -> Option<Vec<usize>> {
+        let mut previous: Vec<Option<(usize, usize)>> = vec![None; self.nodes.len()];
+        let mut visited = vec![false; self.nodes.len()];
+        let mut queue = VecDeque::from([origin]);
+        *visited.get_mut(origin)? = true;
+
+        while let Some(node) = queue.pop_front() {
+            if node == destination {
+                let mut path = Vec::new();
+                let mut current = destination;
+                while let Some((link, from)) = previous[current] {
+                    path.push(link);
+                    current = from;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for (index, link) in self.links.iter().enumerate() {
+                let next = match node {
+                    n if n == link.from => link.to,
+                    n if n == link.to => link.from,
+                    _ => continue,
+                };
+                if !visited[next] {
+                    visited[next] = true;
+                    previous[next] = Some((index, node));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Resolves the links carrying a demand: an explicit route between the two nodes in
+    /// either direction, otherwise the shortest path.
+    fn path_for(&self, origin: usize, destination: usize) -> Result<Vec<usize>, ErlangError> {
+        self.routes
+            .iter()
+            .find(|route| {
+                (route.origin, route.destination) == (origin, destination)
+                    || (route.origin, route.destination) == (destination, origin)
+            })
+            .map(|route| route.links.clone())
+            .or_else(|| self.shortest_path(origin, destination))
+            .filter(|path| !path.is_empty())
+            .ok_or(ErlangError::InvalidParameter(
+                "no route between demand endpoints",
+            ))
+    }
+}
+
+/// Offered traffic between pairs of nodes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrafficMatrix {
+    demands: BTreeMap<(usize, usize), f64>,
+}
+
+impl TrafficMatrix {
+    /// Creates an empty traffic matrix.
+    pub fn new() -> Self {
+        TrafficMatrix::default()
+    }
+
+    /// Sets the traffic in Erlangs offered from `origin` to `destination`.
+    pub fn set(&mut self, origin: usize, destination: usize, traffic: f64) {
+        self.demands.insert((origin, destination), traffic);
+    }
+
+    /// The demands as `((origin, destination), traffic)` pairs in node order.
+    pub fn demands(&self) -> impl Iterator<Item = ((usize, usize), f64)> + '_ {
+        self.demands.iter().map(|(&pair, &traffic)| (pair, traffic))
+    }
+}
+
+/// A demand with the path it follows.
+#[derive(Debug, Clone, PartialEq)]
+struct RoutedDemand {
+    traffic: f64,
+    links: Vec<usize>,
+}
+
+/// Link and end-to-end blocking from the Erlang fixed-point approximation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedPointSolution {
+    /// Blocking probability of each link.
+    pub link_blocking: Vec<f64>,
+    /// Reduced (thinned) load offered to each link in Erlangs.
+    pub link_load: Vec<f64>,
+    /// End-to-end blocking of each demand, in traffic matrix order.
+    pub demand_blocking: Vec<f64>,
+    /// Number of iterations performed.
+    pub iterations: u32,
+    /// Whether the link blocking converged within the iteration limit.
+    pub converged: bool,
+}
+
+fn route_demands(
+    network: &Network,
+    matrix: &TrafficMatrix,
+) -> Result<Vec<RoutedDemand>, ErlangError> {
+    matrix
+        .demands()
+        .map(|((origin, destination), traffic)| {
+            validate_traffic(traffic)?;
+            Ok(RoutedDemand {
+                traffic,
+                links: network.path_for(origin, destination)?,
+            })
+        })
+        .collect()
+}
+
+/// Reduced load on each link: every demand is thinned by the blocking of its other links.
+fn reduced_loads(demands: &[RoutedDemand], link_blocking: &[f64]) -> Vec<f64> {
+    let mut loads = vec![0.0; link_blocking.len()];
+    for demand in demands {
+        for &link in &demand.links {
+            let thinning: f64 = demand
+                .links
+                .iter()
+                .filter(|&&other| other != link)
+                .map(|&other| 1.0 - link_blocking[other])
+                .product();
+            loads[link] += demand.traffic * thinning;
+        }
+    }
+    loads
+}
+
+fn solve(links: &[Link], demands: &[RoutedDemand]) -> FixedPointSolution {
+    let mut link_blocking = vec![0.0; links.len()];
+    let mut link_load = reduced_loads(demands, &link_blocking);
+    let mut iterations = 0;
+    let mut converged = false;
+
+    while iterations < MAX_ITERATIONS && !converged {
+        iterations += 1;
+        let next: Vec<f64> = links
+            .iter()
+            .zip(&link_load)
+            .map(|(link, &load)| erlang_b(load, link.channels))
+            .collect();
+        converged = next
+            .iter()
+            .zip(&link_blocking)
+            .all(|(a, b)| (a - b).abs() < BLOCKING_TOLERANCE);
+        link_blocking = next;
+        link_load = reduced_loads(demands, &link_blocking);
+    }
+
+    let demand_blocking = demands
+        .iter()
+        .map(|demand| {
+            1.0 - demand
+                .links
+                .iter()
+                .map(|&link| 1.0 - link_blocking[link])
+                .product::<f64>()
+        })
+        .collect();
+
+    FixedPointSolution {
+        link_blocking,
+        link_load,
+        demand_blocking,
+        iterations,
+        converged,
+    }
+}
+
+/// Calculates link and end-to-end blocking with the Erlang fixed-point approximation.
+///
+/// Each link is assumed to block independently, seeing the traffic of every demand that
+/// crosses it thinned by the blocking on the demand's other links. The link blocking
+/// probabilities are iterated until they settle.
+///
+/// # Arguments
+/// * `network` - The switches, links (with channel counts) and explicit routes.
+/// * `matrix` - Offered traffic between node pairs.
+///
+/// # Returns
+/// The fixed-point solution, or an error if a demand has invalid traffic or no route.
+pub fn erlang_fixed_point(
+    network: &Network,
+    matrix: &TrafficMatrix,
+) -> Result<FixedPointSolution, ErlangError> {
+    let demands = route_demands(network, matrix)?;
+    Ok(solve(&network.links, &demands))
+}
+
+/// Channel allocation found by `optimize_channels`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelAllocation {
+    /// Channels per link, indexed by link.
+    pub channels: Vec<u32>,
+    /// The fixed-point solution for this allocation.
+    pub solution: FixedPointSolution,
+}
+
+/// Finds a minimal channel allocation per link such that every demand meets an
+/// end-to-end blocking target.
+///
+/// Each demand over `h` links first gets a per-link budget of `1 − (1 − B)^(1/h)`, and
+/// every link is sized for its reduced load at the tightest budget of the demands using
+/// it, repeating until the sizes settle. Channels are then removed greedily, largest
+/// links first, while every demand still meets the target.
+///
+/// # Arguments
+/// * `network` - The switches, links and explicit routes; link channel counts are ignored.
+/// * `matrix` - Offered traffic between node pairs.
+/// * `blocking_probability` - End-to-end blocking target for every demand.
+/// * `channels_max` - Maximum number of channels per link.
+///
+/// # Returns
+/// The allocation, `ErlangError::SearchLimitExceeded` if a link needs `channels_max` or
+/// more channels, or an input error.
+pub fn optimize_channels(
+    network: &Network,
+    matrix: &TrafficMatrix,
+    blocking_probability: f64,
+    channels_max: u32,
+) -> Result<ChannelAllocation, ErlangError> {
+    validate_blocking_probability(blocking_probability)?;
+    let demands = route_demands(network, matrix)?;
+
+    let mut link_target = vec![blocking_probability; network.links.len()];
+    for demand in &demands {
+        let budget = 1.0 - (1.0 - blocking_probability).powf(1.0 / demand.links.len() as f64);
+        for &link in &demand.links {
+            link_target[link] = link_target[link].min(budget);
+        }
+    }
+
+    let mut links = network.links.clone();
+    let mut link_blocking = vec![0.0; links.len()];
+    for _ in 0..MAX_SIZING_ROUNDS {
+        let loads = reduced_loads(&demands, &link_blocking);
+        let mut changed = false;
+        for (index, link) in links.iter_mut().enumerate() {
+            let channels =
+                try_calculate_e1_channels(loads[index], link_target[index], channels_max)?;
+            changed |= channels != link.channels;
+            link.channels = channels;
+        }
+        link_blocking = solve(&links, &demands).link_blocking;
+        if !changed {
+            break;
+        }
+    }
+
+    let meets_target = |links: &[Link]| {
+        let solution = solve(links, &demands);
+        let feasible = solution
+            .demand_blocking
+            .iter()
+            .all(|&blocking| blocking <= blocking_probability);
+        (feasible, solution)
+    };
+
+    // The fixed point may still miss the target slightly; grow the worst links until it holds.
+    loop {
+        let (feasible, solution) = meets_target(&links);
+        if feasible {
+            break;
+        }
+        let (worst, _) = solution.link_blocking.iter().enumerate().fold(
+            (0, f64::MIN),
+            |best, (index, &blocking)| {
+                if blocking > best.1 {
+                    (index, blocking)
+                } else {
+                    best
+                }
+            },
+        );
+        if links[worst].channels + 1 >= channels_max {
+            return Err(ErlangError::SearchLimitExceeded {
+                channels_max,
+                best_blocking: solution.demand_blocking.iter().fold(0.0, |a, &b| a.max(b)),
+            });
+        }
+        links[worst].channels += 1;
+    }
+
+    let mut order: Vec<usize> = (0..links.len()).collect();
+    order.sort_by_key(|&index| std::cmp::Reverse(links[index].channels));
+    let mut trimmed = true;
+    while trimmed {
+        trimmed = false;
+        for &index in &order {
+            while links[index].channels > 0 {
+                links[index].channels -= 1;
+                if meets_target(&links).0 {
+                    trimmed = true;
+                } else {
+                    links[index].channels += 1;
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(ChannelAllocation {
+        channels: links.iter().map(|link| link.channels).collect(),
+        solution: solve(&links, &demands),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Three switches in a line: A - B - C.
+    fn line() -> (Network, TrafficMatrix) {
+        let mut network = Network::new();
+        let a = network.add_node("A");
+        let b = network.add_node("B");
+        let c = network.add_node("C");
+        network.add_link(a, b, 30).unwrap();
+        network.add_link(b, c, 30).unwrap();
+
+        let mut matrix = TrafficMatrix::new();
+        matrix.set(a, b, 10.0);
+        matrix.set(b, c, 10.0);
+        matrix.set(a, c, 10.0);
+        (network, matrix)
+    }
+
+    #[test]
+    fn test_network_routes() {
+        let (mut network, _) = line();
+        assert_eq!(network.shortest_path(0, 2), Some(vec![0, 1]));
+        assert_eq!(network.shortest_path(2, 0), Some(vec![1, 0]));
+        assert!(network.add_route(0, vec![1]).is_err());
+        assert_eq!(network.add_route(2, vec![1, 0]), Ok(0));
+        assert_eq!(network.routes()[0].destination, 0);
+        assert!(network.add_link(0, 0, 10).is_err());
+    }
+
+    #[test]
+    fn test_erlang_fixed_point() {
+        let (network, matrix) = line();
+        let solution = erlang_fixed_point(&network, &matrix).unwrap();
+        assert!(solution.converged);
+
+        // Both links carry 20 Erlangs less a little thinning from the other link.
+        let link = solution.link_blocking[0];
+        assert!(link < erlang_b(20.0, 30) && link > erlang_b(19.0, 30));
+        assert!(solution.link_load[0] < 20.0);
+
+        let two_hop = solution.demand_blocking[1];
+        assert!((two_hop - (1.0 - (1.0 - link).powi(2))).abs() < 1e-12);
+
+        let mut unreachable = matrix.clone();
+        let mut isolated = network.clone();
+        let d = isolated.add_node("D");
+        unreachable.set(0, d, 1.0);
+        assert!(erlang_fixed_point(&isolated, &unreachable).is_err());
+    }
+
+    #[test]
+    fn test_optimize_channels() {
+        let (network, matrix) = line();
+        let allocation = optimize_channels(&network, &matrix, 0.01, 1_000).unwrap();
+        assert!(allocation
+            .solution
+            .demand_blocking
+            .iter()
+            .all(|&blocking| blocking <= 0.01));
+
+        // Removing any single channel breaks the end-to-end target.
+        for index in 0..allocation.channels.len() {
+            let mut reduced = network.clone();
+            let mut channels = allocation.channels.clone();
+            channels[index] -= 1;
+            reduced.set_channels(&channels);
+            let solution = erlang_fixed_point(&reduced, &matrix).unwrap();
+            assert!(solution.demand_blocking.iter().any(|&b| b > 0.01));
+        }
+    }
+}
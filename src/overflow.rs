@@ -0,0 +1,212 @@
+// Overflow traffic modelling with Wilkinson's Equivalent Random Theory.
+
+use crate::continuous::erlang_b_continuous;
+use crate::error::validate_blocking_probability;
+use crate::{erlang_b, ErlangError};
+
+/// Mean and variance of a (possibly peaked) traffic stream.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OverflowTraffic {
+    /// Mean traffic in Erlangs.
+    pub mean: f64,
+    /// Variance of the number of busy channels it would occupy on an infinite group.
+    pub variance: f64,
+}
+
+impl OverflowTraffic {
+    /// Peakedness `Z = variance / mean`; 1 for Poisson traffic, above 1 for overflow traffic.
+    pub fn peakedness(&self) -> f64 {
+        if self.mean > 0.0 {
+            self.variance / self.mean
+        } else {
+            1.0
+        }
+    }
+
+    /// Combines independent parcels offered to the same group by summing means and variances.
+    pub fn combine(parcels: &[OverflowTraffic]) -> OverflowTraffic {
+        OverflowTraffic {
+            mean: parcels.iter().map(|parcel| parcel.mean).sum(),
+            variance: parcels.iter().map(|parcel| parcel.variance).sum(),
+        }
+    }
+}
+
+/// Random (Poisson) traffic and channel count whose overflow reproduces a given mean and
+/// variance. The channel count is generally fractional.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EquivalentRandom {
+    /// Equivalent random traffic in Erlangs.
+    pub traffic: f64,
+    /// Equivalent number of primary channels.
+    pub channels: f64,
+}
+
+/// Calculates the mean and variance of the traffic overflowing a primary group,
+/// using the Riordan formula `V = M × (1 − M + A / (N + 1 − A + M))`.
+///
+/// # Arguments
+/// * `traffic` - Random traffic offered to the primary group in Erlangs.
+/// * `channels` - Channels in the primary (high-usage) group.
+///
+/// # Returns
+/// The overflow mean `M = A × B(A, N)` and its variance.
+pub fn overflow_traffic(traffic: f64, channels: u32) -> OverflowTraffic {
+    let mean = traffic * erlang_b(traffic, channels);
+    let variance = mean * (1.0 - mean + traffic / (channels as f64 + 1.0 - traffic + mean));
+    OverflowTraffic { mean, variance }
+}
+
+/// Finds the equivalent random traffic and channel count with Rapp's approximation
+/// `A* = V + 3Z(Z − 1)`, `N* = A*(M + Z) / (M + Z − 1) − M − 1`.
+///
+/// Traffic that is not peaked (`Z <= 1`) is treated as random, with no primary channels.
+///
+/// # Arguments
+/// * `overflow` - Mean and variance of the combined overflow traffic.
+///
+/// # Returns
+/// The equivalent random system.
+pub fn equivalent_random(overflow: OverflowTraffic) -> EquivalentRandom {
+    let mean = overflow.mean;
+    let z = overflow.peakedness();
+    if z <= 1.0 {
+        return EquivalentRandom {
+            traffic: mean,
+            channels: 0.0,
+        };
+    }
+
+    let traffic = overflow.variance + 3.0 * z * (z - 1.0);
+    let channels = traffic * (mean + z) / (mean + z - 1.0) - mean - 1.0;
+    EquivalentRandom {
+        traffic,
+        channels: channels.max(0.0),
+    }
+}
+
+/// Searches for the smallest channel count whose blocking meets the target.
+fn search_channels(
+    blocking_probability: f64,
+    channels_max: u32,
+    blocking: impl Fn(u32) -> f64,
+) -> Result<u32, ErlangError> {
+    let mut best_blocking = 1.0;
+    for channels in 0..channels_max {
+        best_blocking = blocking(channels);
+        if best_blocking <= blocking_probability {
+            return Ok(channels);
+        }
+    }
+
+    Err(ErlangError::SearchLimitExceeded {
+        channels_max,
+        best_blocking,
+    })
+}
+
+/// Sizes a final (tandem) route for several overflow parcels using Equivalent Random
+/// Theory. The final route of `n` channels sees blocking `A* × B(A*, N* + n) / M`.
+///
+/// # Arguments
+/// * `parcels` - Overflow traffic from each high-usage route, e.g. from `overflow_traffic`.
+/// * `blocking_probability` - Desired blocking of the overflow traffic on the final route.
+/// * `channels_max` - Maximum number of channels to search for.
+///
+/// # Returns
+/// The number of final-route channels, `ErlangError::SearchLimitExceeded` if none below
+/// `channels_max` suffices, or `ErlangError::InvalidBlockingProbability`.
+pub fn final_route_channels_ert(
+    parcels: &[OverflowTraffic],
+    blocking_probability: f64,
+    channels_max: u32,
+) -> Result<u32, ErlangError> {
+    validate_blocking_probability(blocking_probability)?;
+    let combined = OverflowTraffic::combine(parcels);
+    if combined.mean <= 0.0 {
+        return Ok(0);
+    }
+
+    let equivalent = equivalent_random(combined);
+    search_channels(blocking_probability, channels_max, |channels| {
+        let lost = equivalent.traffic
+            * erlang_b_continuous(equivalent.traffic, equivalent.channels + channels as f64);
+        lost / combined.mean
+    })
+}
+
+/// Sizes a final route for several overflow parcels using the Fredericks-Hayward
+/// approximation: `N` channels offered peaked traffic `(M, Z)` block like `N / Z`
+/// channels offered random traffic `M / Z`.
+///
+/// # Arguments
+/// * `parcels` - Overflow traffic from each high-usage route.
+/// * `blocking_probability` - Desired blocking of the overflow traffic on the final route.
+/// * `channels_max` - Maximum number of channels to search for.
+///
+/// # Returns
+/// The number of final-route channels, `ErlangError::SearchLimitExceeded` if none below
+/// `channels_max` suffices, or `ErlangError::InvalidBlockingProbability`.
+pub fn final_route_channels_fredericks_hayward(
+    parcels: &[OverflowTraffic],
+    blocking_probability: f64,
+    channels_max: u32,
+) -> Result<u32, ErlangError> {
+    validate_blocking_probability(blocking_probability)?;
+    let combined = OverflowTraffic::combine(parcels);
+    if combined.mean <= 0.0 {
+        return Ok(0);
+    }
+
+    let z = combined.peakedness();
+    search_channels(blocking_probability, channels_max, |channels| {
+        erlang_b_continuous(combined.mean / z, channels as f64 / z)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculate_e1_channels;
+
+    #[test]
+    fn test_overflow_traffic() {
+        let overflow = overflow_traffic(10.0, 10);
+        assert!((overflow.mean - 2.146).abs() < 1e-3);
+        assert!((overflow.peakedness() - 2.0326).abs() < 1e-3);
+
+        // Overflow of a group with no channels is the offered Poisson traffic itself.
+        let none = overflow_traffic(5.0, 0);
+        assert_eq!(none.mean, 5.0);
+        assert!((none.peakedness() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_equivalent_random_round_trip() {
+        // Rapp's approximation roughly recovers the primary group that produced the overflow.
+        let overflow = overflow_traffic(10.0, 10);
+        let equivalent = equivalent_random(overflow);
+        assert!((equivalent.traffic - 10.0).abs() < 1.0);
+        assert!((equivalent.channels - 10.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_final_route_channels() {
+        let parcels = [overflow_traffic(10.0, 10), overflow_traffic(15.0, 14)];
+        let mean = OverflowTraffic::combine(&parcels).mean;
+        let poisson = calculate_e1_channels(mean, 0.01, 1_000).unwrap();
+
+        // Peaked overflow needs more channels than its mean suggests.
+        let ert = final_route_channels_ert(&parcels, 0.01, 1_000).unwrap();
+        let fh = final_route_channels_fredericks_hayward(&parcels, 0.01, 1_000).unwrap();
+        assert!(ert > poisson);
+        assert!(fh > poisson);
+        assert!(ert.abs_diff(fh) <= 2);
+
+        assert_eq!(final_route_channels_ert(&[], 0.01, 1_000), Ok(0));
+        assert!(matches!(
+            final_route_channels_ert(&parcels, 0.01, 3),
+            Err(ErlangError::SearchLimitExceeded { .. })
+        ));
+    }
+}
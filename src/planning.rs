@@ -0,0 +1,308 @@
+// Multi-period traffic growth and span augmentation planning.
+
+use crate::error::validate_traffic;
+use crate::inverse::max_traffic;
+use crate::trunk::{dimension_trunks, TrunkType};
+use crate::ErlangError;
+
+/// How busy-hour traffic grows from one period to the next.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GrowthModel {
+    /// A fixed number of Erlangs added each period.
+    Linear(f64),
+    /// A fixed growth rate per period, e.g. `0.08` for 8%.
+    Compound(f64),
+    /// An S-curve levelling off at `saturation` Erlangs, with growth `rate` per period
+    /// while traffic is still small.
+    Logistic {
+        /// Traffic in Erlangs that the curve approaches but never reaches.
+        saturation: f64,
+        /// Growth rate per period while the traffic is far below saturation, e.g. `0.5`.
+        rate: f64,
+    },
+}
+
+impl GrowthModel {
+    fn validate(self, base_traffic: f64) -> Result<(), ErlangError> {
+        let valid = match self {
+            GrowthModel::Linear(increment) => increment.is_finite(),
+            GrowthModel::Compound(rate) => rate > -1.0 && rate.is_finite(),
+            GrowthModel::Logistic { saturation, rate } => {
+                saturation > 0.0 && saturation.is_finite() && rate.is_finite() && base_traffic > 0.0
+            }
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(ErlangError::InvalidParameter(
+                "growth must be finite, compound rates above -100% and logistic curves need positive traffic and saturation",
+            ))
+        }
+    }
+
+    /// Projects the traffic after a number of periods, which may be fractional.
+    ///
+    /// # Arguments
+    /// * `base_traffic` - Traffic in Erlangs at period 0.
+    /// * `period` - Periods elapsed since the base.
+    ///
+    /// # Returns
+    /// The projected traffic in Erlangs, never below zero.
+    pub fn traffic(self, base_traffic: f64, period: f64) -> f64 {
+        match self {
+            GrowthModel::Linear(increment) => (base_traffic + increment * period).max(0.0),
+            GrowthModel::Compound(rate) => base_traffic * (1.0 + rate).powf(period),
+            GrowthModel::Logistic { saturation, rate } => {
+                saturation
+                    / (1.0 + (saturation - base_traffic) / base_traffic * (-rate * period).exp())
+            }
+        }
+    }
+
+    /// Finds the (fractional) period at which the traffic first reaches a level.
+    ///
+    /// # Returns
+    /// The period, `0.0` if the base already reaches it, or `None` if the traffic never
+    /// grows to it.
+    pub fn period_reaching(self, base_traffic: f64, traffic: f64) -> Option<f64> {
+        if traffic <= base_traffic {
+            return Some(0.0);
+        }
+        let period = match self {
+            GrowthModel::Linear(increment) => (traffic - base_traffic) / increment,
+            GrowthModel::Compound(rate) => (traffic / base_traffic).ln() / rate.ln_1p(),
+            GrowthModel::Logistic { saturation, rate } => {
+                if traffic >= saturation {
+                    return None;
+                }
+                -((saturation / traffic - 1.0) * base_traffic / (saturation - base_traffic)).ln()
+                    / rate
+            }
+        };
+        (period > 0.0 && period.is_finite()).then_some(period)
+    }
+}
+
+/// Growth, horizon and dimensioning rules of an augmentation plan.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlanConfig {
+    /// How the traffic grows.
+    pub growth: GrowthModel,
+    /// Number of periods to plan after the base period.
+    pub horizon: u32,
+    /// Desired blocking probability (between 0 and 1).
+    pub blocking_probability: f64,
+    /// The trunk type providing the spans.
+    pub trunk_type: TrunkType,
+    /// Time from ordering a span to putting it into service, in periods (may be fractional).
+    pub lead_time: f64,
+    /// Maximum number of channels to search for in each period.
+    pub channels_max: u32,
+}
+
+/// Dimensioning of one period of the plan.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlanPeriod {
+    /// Period number, 0 being the base.
+    pub period: u32,
+    /// Projected busy-hour traffic in Erlangs.
+    pub traffic: f64,
+    /// Channels required, from `calculate_e1_channels`.
+    pub channels: u32,
+    /// Spans required to carry the channels.
+    pub spans: u32,
+}
+
+/// One additional span in the augmentation schedule.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Augmentation {
+    /// The span being added, counting from 1 (e.g. `3` for the third span).
+    pub span: u32,
+    /// Traffic above which the spans already in place miss the blocking target.
+    pub traffic_threshold: f64,
+    /// Fractional period at which the projected traffic crosses the threshold.
+    pub crossing: f64,
+    /// First whole period of the plan that needs the span.
+    pub period: u32,
+    /// Latest (fractional) period at which to order the span: the crossing minus the lead time.
+    pub order_by: f64,
+    /// Whether the order date has already passed at the base period.
+    pub late: bool,
+}
+
+/// Per-period dimensioning and the resulting augmentation schedule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AugmentationPlan {
+    /// One entry per period from the base to the horizon.
+    pub periods: Vec<PlanPeriod>,
+    /// Each span added after the base period, in the order they are needed.
+    pub augmentations: Vec<Augmentation>,
+}
+
+/// Projects the traffic over a planning horizon, dimensions every period and schedules
+/// each extra span with the date it must be ordered.
+///
+/// The spans required at period 0 are taken as installed. Every later span is needed once
+/// the traffic passes the maximum traffic the spans before it can carry at the blocking
+/// target (`inverse::max_traffic`); the crossing is solved from the growth curve, so it
+/// can fall between periods.
+///
+/// # Arguments
+/// * `base_traffic` - Busy-hour traffic in Erlangs at period 0.
+/// * `config` - Growth model, horizon, blocking target, trunk type and lead time.
+///
+/// # Returns
+/// The plan, `ErlangError::InvalidParameter` for an invalid growth model or a negative
+/// lead time, or any error from `try_calculate_e1_channels`.
+pub fn plan_augmentation(
+    base_traffic: f64,
+    config: &PlanConfig,
+) -> Result<AugmentationPlan, ErlangError> {
+    validate_traffic(base_traffic)?;
+    config.growth.validate(base_traffic)?;
+    if !(config.lead_time >= 0.0 && config.lead_time.is_finite()) {
+        return Err(ErlangError::InvalidParameter(
+            "lead time must be finite and non-negative",
+        ));
+    }
+
+    let periods = (0..=config.horizon)
+        .map(|period| {
+            let traffic = config.growth.traffic(base_traffic, period as f64);
+            let plan = dimension_trunks(
+                traffic,
+                config.blocking_probability,
+                config.channels_max,
+                config.trunk_type,
+            )?;
+            Ok(PlanPeriod {
+                period,
+                traffic,
+                channels: plan.channels,
+                spans: plan.spans,
+            })
+        })
+        .collect::<Result<Vec<_>, ErlangError>>()?;
+
+    let bearer_channels = config.trunk_type.bearer_channels();
+    let mut augmentations = Vec::new();
+    let mut installed = periods[0].spans;
+    for entry in &periods[1..] {
+        while installed < entry.spans {
+            let traffic_threshold =
+                max_traffic(installed * bearer_channels, config.blocking_probability)
+                    .map_or(0.0, |inverse| inverse.traffic);
+            let crossing = config
+                .growth
+                .period_reaching(base_traffic, traffic_threshold)
+                .unwrap_or(entry.period as f64)
+                .min(entry.period as f64);
+            let order_by = crossing - config.lead_time;
+            installed += 1;
+            augmentations.push(Augmentation {
+                span: installed,
+                traffic_threshold,
+                crossing,
+                period: entry.period,
+                order_by,
+                late: order_by < 0.0,
+            });
+        }
+    }
+
+    Ok(AugmentationPlan {
+        periods,
+        augmentations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculate_e1_channels;
+
+    fn config(growth: GrowthModel) -> PlanConfig {
+        PlanConfig {
+            growth,
+            horizon: 5,
+            blocking_probability: 0.01,
+            trunk_type: TrunkType::E1Pri,
+            lead_time: 0.5,
+            channels_max: 10_000,
+        }
+    }
+
+    #[test]
+    fn test_growth_models() {
+        assert_eq!(GrowthModel::Linear(5.0).traffic(20.0, 3.0), 35.0);
+        assert!((GrowthModel::Compound(0.1).traffic(20.0, 2.0) - 24.2).abs() < 1e-12);
+        let logistic = GrowthModel::Logistic {
+            saturation: 100.0,
+            rate: 0.5,
+        };
+        assert!((logistic.traffic(20.0, 0.0) - 20.0).abs() < 1e-12);
+        assert!(logistic.traffic(20.0, 50.0) < 100.0);
+        assert!((logistic.traffic(20.0, 50.0) - 100.0).abs() < 1e-6);
+        for growth in [
+            GrowthModel::Linear(5.0),
+            GrowthModel::Compound(0.1),
+            logistic,
+        ] {
+            let period = growth.period_reaching(20.0, 40.0).unwrap();
+            assert!((growth.traffic(20.0, period) - 40.0).abs() < 1e-9);
+        }
+        assert_eq!(logistic.period_reaching(20.0, 120.0), None);
+        assert_eq!(GrowthModel::Linear(-1.0).period_reaching(20.0, 40.0), None);
+    }
+
+    #[test]
+    fn test_plan_augmentation() {
+        let plan = plan_augmentation(20.0, &config(GrowthModel::Compound(0.25))).unwrap();
+        assert_eq!(plan.periods.len(), 6);
+        for entry in &plan.periods {
+            assert_eq!(
+                Some(entry.channels),
+                calculate_e1_channels(entry.traffic, 0.01, 10_000)
+            );
+            assert_eq!(entry.spans, entry.channels.div_ceil(30));
+        }
+
+        let added = plan.periods[5].spans - plan.periods[0].spans;
+        assert_eq!(plan.augmentations.len(), added as usize);
+        for augmentation in &plan.augmentations {
+            // The span is needed in the first period after the crossing.
+            assert_eq!(augmentation.period, augmentation.crossing.ceil() as u32);
+            assert_eq!(
+                plan.periods[augmentation.period as usize].spans,
+                augmentation.span
+            );
+            assert_eq!(augmentation.order_by, augmentation.crossing - 0.5);
+            let channels = (augmentation.span - 1) * 30;
+            assert!(
+                calculate_e1_channels(augmentation.traffic_threshold * 1.001, 0.01, 10_000)
+                    .unwrap()
+                    > channels
+            );
+        }
+    }
+
+    #[test]
+    fn test_late_orders_and_saturation() {
+        let mut urgent = config(GrowthModel::Linear(30.0));
+        urgent.lead_time = 2.0;
+        let plan = plan_augmentation(20.0, &urgent).unwrap();
+        assert!(plan.augmentations[0].late);
+
+        // Traffic levelling off below one span's capacity never needs a second span.
+        let flat = plan_augmentation(
+            10.0,
+            &config(GrowthModel::Logistic {
+                saturation: 15.0,
+                rate: 1.0,
+            }),
+        )
+        .unwrap();
+        assert!(flat.augmentations.is_empty());
+        assert!(plan_augmentation(20.0, &config(GrowthModel::Compound(-2.0))).is_err());
+    }
+}
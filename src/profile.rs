@@ -0,0 +1,423 @@
+// Busy-hour traffic intensity from subscriber calling behaviour.
+
+use crate::{try_calculate_e1_channels, ErlangError};
+use std::fmt;
+
+/// Unit of a holding time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeUnit {
+    /// Seconds, as in most CDRs and switch statistics.
+    Seconds,
+    /// Minutes, as in billing and planning spreadsheets.
+    Minutes,
+    /// Hours.
+    Hours,
+}
+
+impl TimeUnit {
+    /// Converts a duration in this unit to hours.
+    pub fn to_hours(self, value: f64) -> f64 {
+        match self {
+            TimeUnit::Seconds => value / 3_600.0,
+            TimeUnit::Minutes => value / 60.0,
+            TimeUnit::Hours => value,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            TimeUnit::Seconds => "s",
+            TimeUnit::Minutes => "min",
+            TimeUnit::Hours => "h",
+        }
+    }
+}
+
+/// How the per-user call rate in the busy hour was given.
+///
+/// Traffic is carried by completed calls for their mean holding time. Busy-hour call
+/// attempts also count attempts that are never answered (no answer, busy, abandoned), so
+/// they are converted to completed calls with a completion ratio; attempts that fail
+/// hold a channel only briefly and are not counted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CallRate {
+    /// Busy-hour call attempts per user, with the share of attempts that complete.
+    BhcaPerUser {
+        /// Call attempts per user in the busy hour.
+        attempts: f64,
+        /// Share of attempts that complete as calls (0 to 1), e.g. `0.7`.
+        completion_ratio: f64,
+    },
+    /// Completed calls per user in the busy hour.
+    CallsPerUser(f64),
+}
+
+impl CallRate {
+    /// Completed calls per user in the busy hour.
+    pub fn calls_per_user(self) -> f64 {
+        match self {
+            CallRate::BhcaPerUser {
+                attempts,
+                completion_ratio,
+            } => attempts * completion_ratio,
+            CallRate::CallsPerUser(calls) => calls,
+        }
+    }
+
+    fn validate(self) -> Result<(), ErlangError> {
+        let (rate, completion_ratio) = match self {
+            CallRate::BhcaPerUser {
+                attempts,
+                completion_ratio,
+            } => (attempts, completion_ratio),
+            CallRate::CallsPerUser(calls) => (calls, 1.0),
+        };
+        if !(rate >= 0.0 && rate.is_finite()) {
+            return Err(ErlangError::InvalidParameter(
+                "call rate must be finite and non-negative",
+            ));
+        }
+        if !(0.0..=1.0).contains(&completion_ratio) {
+            return Err(ErlangError::InvalidParameter(
+                "completion ratio must be between 0 and 1",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Builder for the busy-hour traffic offered by a group of users.
+///
+/// Offered traffic is `active users × calls per user × mean holding time in hours`,
+/// the standard definition of traffic intensity in Erlangs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrafficProfile {
+    users: u32,
+    call_rate: Option<CallRate>,
+    holding_time: Option<(f64, TimeUnit)>,
+    active_percent: f64,
+    inbound_percent: f64,
+}
+
+/// Step-by-step derivation of the offered traffic from a `TrafficProfile`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrafficBreakdown {
+    /// Total number of users.
+    pub users: u32,
+    /// Percentage of users making calls in the busy hour.
+    pub active_percent: f64,
+    /// Users making calls in the busy hour.
+    pub active_users: f64,
+    /// The per-user call rate as given.
+    pub call_rate: CallRate,
+    /// Completed calls in the busy hour by all active users.
+    pub busy_hour_calls: f64,
+    /// Mean holding time as given, with its unit.
+    pub holding_time: (f64, TimeUnit),
+    /// Mean holding time in hours.
+    pub holding_time_hours: f64,
+    /// Total offered traffic in Erlangs.
+    pub traffic: f64,
+    /// Share of the traffic arriving from the network, in percent.
+    pub inbound_percent: f64,
+    /// Inbound traffic in Erlangs.
+    pub inbound_traffic: f64,
+    /// Outbound traffic in Erlangs.
+    pub outbound_traffic: f64,
+}
+
+impl fmt::Display for TrafficBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (holding, unit) = self.holding_time;
+        writeln!(
+            f,
+            "active users:    {} users × {}% = {}",
+            self.users, self.active_percent, self.active_users
+        )?;
+        match self.call_rate {
+            CallRate::BhcaPerUser {
+                attempts,
+                completion_ratio,
+            } => writeln!(
+                f,
+                "busy-hour calls: {} × {attempts} BHCA per user × {completion_ratio} completed = {}",
+                self.active_users, self.busy_hour_calls
+            )?,
+            CallRate::CallsPerUser(calls) => writeln!(
+                f,
+                "busy-hour calls: {} × {calls} calls per user = {}",
+                self.active_users, self.busy_hour_calls
+            )?,
+        }
+        writeln!(
+            f,
+            "holding time:    {holding} {} = {} h",
+            unit.symbol(),
+            self.holding_time_hours
+        )?;
+        writeln!(
+            f,
+            "traffic:         {} calls × {} h = {} Erl",
+            self.busy_hour_calls, self.holding_time_hours, self.traffic
+        )?;
+        write!(
+            f,
+            "split:           {} Erl inbound ({}%), {} Erl outbound",
+            self.inbound_traffic, self.inbound_percent, self.outbound_traffic
+        )
+    }
+}
+
+fn validate_percent(percent: f64, message: &'static str) -> Result<f64, ErlangError> {
+    if (0.0..=100.0).contains(&percent) {
+        Ok(percent)
+    } else {
+        Err(ErlangError::InvalidParameter(message))
+    }
+}
+
+impl TrafficProfile {
+    /// Starts a profile for a number of users, all active, with traffic split evenly
+    /// between inbound and outbound.
+    pub fn new(users: u32) -> Self {
+        TrafficProfile {
+            users,
+            call_rate: None,
+            holding_time: None,
+            active_percent: 100.0,
+            inbound_percent: 50.0,
+        }
+    }
+
+    /// Sets the busy-hour call attempts per user and the share of them that complete
+    /// (0 to 1). Replaces any call rate set before.
+    pub fn bhca_per_user(mut self, attempts: f64, completion_ratio: f64) -> Self {
+        self.call_rate = Some(CallRate::BhcaPerUser {
+            attempts,
+            completion_ratio,
+        });
+        self
+    }
+
+    /// Sets the completed calls per user in the busy hour. Replaces any call rate set
+    /// before.
+    pub fn calls_per_user(mut self, calls: f64) -> Self {
+        self.call_rate = Some(CallRate::CallsPerUser(calls));
+        self
+    }
+
+    /// Sets the mean holding time of a call.
+    pub fn holding_time(mut self, value: f64, unit: TimeUnit) -> Self {
+        self.holding_time = Some((value, unit));
+        self
+    }
+
+    /// Sets the percentage of users making calls in the busy hour (0 to 100).
+    pub fn active_users_percent(mut self, percent: f64) -> Self {
+        self.active_percent = percent;
+        self
+    }
+
+    /// Sets the percentage of traffic arriving from the network (0 to 100); the rest is
+    /// outbound.
+    pub fn inbound_percent(mut self, percent: f64) -> Self {
+        self.inbound_percent = percent;
+        self
+    }
+
+    /// Validates the profile and derives the offered traffic step by step.
+    ///
+    /// # Returns
+    /// The breakdown, `ErlangError::InvalidParameter` if the call rate or holding time is
+    /// missing, negative or not finite, or a completion ratio or percentage is out of range,
+    /// or
+    /// `ErlangError::NumericOverflow` if the traffic is not finite.
+    pub fn breakdown(&self) -> Result<TrafficBreakdown, ErlangError> {
+        let call_rate = self.call_rate.ok_or(ErlangError::InvalidParameter(
+            "call rate is required: set bhca_per_user or calls_per_user",
+        ))?;
+        call_rate.validate()?;
+        let holding_time = self
+            .holding_time
+            .ok_or(ErlangError::InvalidParameter("holding time is required"))?;
+        if !(holding_time.0 >= 0.0 && holding_time.0.is_finite()) {
+            return Err(ErlangError::InvalidParameter(
+                "holding time must be finite and non-negative",
+            ));
+        }
+        let active_percent = validate_percent(
+            self.active_percent,
+            "active users percentage must be between 0 and 100",
+        )?;
+        let inbound_percent = validate_percent(
+            self.inbound_percent,
+            "inbound percentage must be between 0 and 100",
+        )?;
+
+        let active_users = self.users as f64 * active_percent / 100.0;
+        let busy_hour_calls = active_users * call_rate.calls_per_user();
+        let holding_time_hours = holding_time.1.to_hours(holding_time.0);
+        let traffic = busy_hour_calls * holding_time_hours;
+        if !traffic.is_finite() {
+            return Err(ErlangError::NumericOverflow);
+        }
+        let inbound_traffic = traffic * inbound_percent / 100.0;
+
+        Ok(TrafficBreakdown {
+            users: self.users,
+            active_percent,
+            active_users,
+            call_rate,
+            busy_hour_calls,
+            holding_time,
+            holding_time_hours,
+            traffic,
+            inbound_percent,
+            inbound_traffic,
+            outbound_traffic: traffic - inbound_traffic,
+        })
+    }
+
+    /// Calculates the offered traffic in Erlangs.
+    ///
+    /// # Returns
+    /// The traffic, or any error from `breakdown`.
+    pub fn traffic(&self) -> Result<f64, ErlangError> {
+        self.breakdown().map(|breakdown| breakdown.traffic)
+    }
+
+    /// Calculates the channels required for the total traffic on bothway trunks.
+    ///
+    /// # Arguments
+    /// * `blocking_probability` - Desired blocking probability (between 0 and 1).
+    /// * `channels_max` - Maximum number of channels to search for.
+    ///
+    /// # Returns
+    /// The number of channels, or any error from `breakdown` or `try_calculate_e1_channels`.
+    pub fn calculate_channels(
+        &self,
+        blocking_probability: f64,
+        channels_max: u32,
+    ) -> Result<u32, ErlangError> {
+        try_calculate_e1_channels(self.traffic()?, blocking_probability, channels_max)
+    }
+
+    /// Calculates the channels required on separate one-way inbound and outbound trunk
+    /// groups.
+    ///
+    /// # Arguments
+    /// * `blocking_probability` - Desired blocking probability on each group.
+    /// * `channels_max` - Maximum number of channels to search for per group.
+    ///
+    /// # Returns
+    /// The inbound and outbound channel counts, or any error from `breakdown` or
+    /// `try_calculate_e1_channels`.
+    pub fn calculate_one_way_channels(
+        &self,
+        blocking_probability: f64,
+        channels_max: u32,
+    ) -> Result<(u32, u32), ErlangError> {
+        let breakdown = self.breakdown()?;
+        Ok((
+            try_calculate_e1_channels(
+                breakdown.inbound_traffic,
+                blocking_probability,
+                channels_max,
+            )?,
+            try_calculate_e1_channels(
+                breakdown.outbound_traffic,
+                blocking_probability,
+                channels_max,
+            )?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculate_e1_channels;
+
+    #[test]
+    fn test_breakdown() {
+        let breakdown = TrafficProfile::new(500)
+            .calls_per_user(2.0)
+            .holding_time(3.0, TimeUnit::Minutes)
+            .active_users_percent(40.0)
+            .inbound_percent(25.0)
+            .breakdown()
+            .unwrap();
+        assert_eq!(breakdown.active_users, 200.0);
+        assert_eq!(breakdown.busy_hour_calls, 400.0);
+        assert_eq!(breakdown.holding_time_hours, 0.05);
+        assert_eq!(breakdown.traffic, 20.0);
+        assert_eq!(breakdown.inbound_traffic, 5.0);
+        assert_eq!(breakdown.outbound_traffic, 15.0);
+        assert!(breakdown
+            .to_string()
+            .contains("traffic:         400 calls × 0.05 h = 20 Erl"));
+    }
+
+    #[test]
+    fn test_units_agree() {
+        let seconds = TrafficProfile::new(60)
+            .calls_per_user(1.0)
+            .holding_time(120.0, TimeUnit::Seconds);
+        let hours = seconds.clone().holding_time(1.0 / 30.0, TimeUnit::Hours);
+        assert!((seconds.traffic().unwrap() - 2.0).abs() < 1e-12);
+        assert!((hours.traffic().unwrap() - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_bhca_completion() {
+        // 2 attempts per user of which 70% complete are 1.4 calls per user.
+        let attempts = TrafficProfile::new(500)
+            .bhca_per_user(2.0, 0.7)
+            .holding_time(3.0, TimeUnit::Minutes);
+        let calls = attempts.clone().calls_per_user(2.0);
+        assert!((attempts.traffic().unwrap() - 35.0).abs() < 1e-12);
+        assert_eq!(calls.traffic(), Ok(50.0));
+        assert!(attempts
+            .breakdown()
+            .unwrap()
+            .to_string()
+            .contains("busy-hour calls: 500 × 2 BHCA per user × 0.7 completed = 70"));
+        assert!(matches!(
+            attempts.bhca_per_user(2.0, 1.5).traffic(),
+            Err(ErlangError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_validation() {
+        let missing_rate = TrafficProfile::new(10).holding_time(3.0, TimeUnit::Minutes);
+        assert!(matches!(
+            missing_rate.traffic(),
+            Err(ErlangError::InvalidParameter(_))
+        ));
+        let negative = missing_rate.clone().calls_per_user(-1.0);
+        assert!(negative.traffic().is_err());
+        let percent = missing_rate
+            .clone()
+            .calls_per_user(1.0)
+            .active_users_percent(120.0);
+        assert!(percent.traffic().is_err());
+        let missing_time = TrafficProfile::new(10).calls_per_user(1.0);
+        assert!(missing_time.traffic().is_err());
+    }
+
+    #[test]
+    fn test_calculate_channels() {
+        let profile = TrafficProfile::new(500)
+            .calls_per_user(2.0)
+            .holding_time(3.0, TimeUnit::Minutes)
+            .active_users_percent(40.0);
+        assert_eq!(
+            profile.calculate_channels(0.01, 1_000).ok(),
+            calculate_e1_channels(20.0, 0.01, 1_000)
+        );
+        let (inbound, outbound) = profile.calculate_one_way_channels(0.01, 1_000).unwrap();
+        assert_eq!(inbound, outbound);
+        assert!(inbound + outbound > profile.calculate_channels(0.01, 1_000).unwrap());
+    }
+}
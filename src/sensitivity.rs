@@ -0,0 +1,318 @@
+// What-if analysis of a dimensioning result: blocking under traffic and span changes.
+
+use crate::continuous::erlang_b_derivative_traffic;
+use crate::error::validate_traffic;
+use crate::inverse::max_traffic;
+use crate::table::percent_heading;
+use crate::trunk::{dimension_trunks, TrunkType};
+use crate::{erlang_b, ErlangError};
+use std::fmt;
+
+/// The dimensioning question being analysed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scenario {
+    /// Busy-hour traffic in Erlangs.
+    pub traffic: f64,
+    /// Desired blocking probability (between 0 and 1).
+    pub blocking_probability: f64,
+    /// The trunk type providing the spans.
+    pub trunk_type: TrunkType,
+}
+
+/// Rows and columns of the sensitivity grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensitivityConfig {
+    /// Relative traffic changes, one column each, e.g. `0.2` for 20% more traffic.
+    pub traffic_deltas: Vec<f64>,
+    /// Spans added to or removed from the dimensioned count, one row each.
+    pub span_deltas: Vec<i32>,
+    /// Maximum number of channels to search for when dimensioning.
+    pub channels_max: u32,
+}
+
+impl Default for SensitivityConfig {
+    /// Traffic from 20% lower to 20% higher in steps of 10%, with one span fewer or more.
+    fn default() -> Self {
+        SensitivityConfig {
+            traffic_deltas: vec![-0.2, -0.1, 0.0, 0.1, 0.2],
+            span_deltas: vec![-1, 0, 1],
+            channels_max: 10_000,
+        }
+    }
+}
+
+/// Blocking of one span count at one traffic level.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SensitivityCell {
+    /// Offered traffic in Erlangs.
+    pub traffic: f64,
+    /// Erlang B blocking probability.
+    pub blocking: f64,
+    /// Whether the blocking meets the scenario's target.
+    pub meets_target: bool,
+}
+
+/// One span count of the sensitivity grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensitivityRow {
+    /// Spans relative to the dimensioned count.
+    pub span_delta: i32,
+    /// Number of spans.
+    pub spans: u32,
+    /// Bearer channels provided by the spans.
+    pub channels: u32,
+    /// One cell per traffic delta, in column order.
+    pub cells: Vec<SensitivityCell>,
+}
+
+/// Operating point of a scenario and its sensitivity to traffic and capacity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensitivityReport {
+    /// The scenario analysed.
+    pub scenario: Scenario,
+    /// Channels required, from `calculate_e1_channels`.
+    pub required_channels: u32,
+    /// Spans carrying the required channels.
+    pub spans: u32,
+    /// Bearer channels installed with those spans: the operating point.
+    pub installed_channels: u32,
+    /// Blocking at the operating point.
+    pub blocking: f64,
+    /// `dB/dA` at the operating point, per Erlang.
+    pub blocking_derivative: f64,
+    /// Elasticity `(A/B) × dB/dA`: the percentage change in blocking per 1% more traffic.
+    pub elasticity: f64,
+    /// Largest traffic the installed channels carry at the target blocking, from
+    /// `inverse::max_traffic`.
+    pub max_traffic: f64,
+    /// Traffic that can be added before the target is violated, in Erlangs.
+    pub headroom: f64,
+    /// Relative traffic changes of the grid columns.
+    pub traffic_deltas: Vec<f64>,
+    /// The grid, one row per span delta.
+    pub rows: Vec<SensitivityRow>,
+}
+
+impl fmt::Display for SensitivityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "operating point: {} Erl on {} spans ({} channels), blocking {:.4}",
+            self.scenario.traffic, self.spans, self.installed_channels, self.blocking
+        )?;
+        writeln!(
+            f,
+            "elasticity:      {:.3} ({:.6} per Erl)",
+            self.elasticity, self.blocking_derivative
+        )?;
+        writeln!(
+            f,
+            "headroom:        {:.3} Erl up to {:.3} Erl at {}",
+            self.headroom, self.max_traffic, self.scenario.blocking_probability
+        )?;
+        write!(f, "{:>6}  {:>8}", "spans", "channels")?;
+        for delta in &self.traffic_deltas {
+            let sign = if *delta < 0.0 { '-' } else { '+' };
+            write!(
+                f,
+                "  {:>8}",
+                format!("{sign}{}", percent_heading(delta.abs()))
+            )?;
+        }
+        for row in &self.rows {
+            write!(f, "\n{:>6}  {:>8}", row.spans, row.channels)?;
+            for cell in &row.cells {
+                let marker = if cell.meets_target { ' ' } else { '*' };
+                write!(f, "  {:>7.4}{marker}", cell.blocking)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Analyses how the blocking of a dimensioned trunk group responds to traffic changes and
+/// to adding or removing spans.
+///
+/// The operating point is the scenario's traffic on all the bearer channels of the spans
+/// it needs, so the spare channels of the last span count towards the headroom.
+///
+/// # Arguments
+/// * `scenario` - Traffic, blocking target and trunk type.
+/// * `config` - Traffic and span deltas of the grid, and the channel search limit.
+///
+/// # Returns
+/// The report, `ErlangError::InvalidTraffic` for traffic that is not positive,
+/// `ErlangError::InvalidParameter` for a traffic delta of -100% or below,
+/// `ErlangError::NumericOverflow` if a row's channels do not fit in a `u32`, or any error
+/// from `try_calculate_e1_channels`.
+pub fn sensitivity_report(
+    scenario: &Scenario,
+    config: &SensitivityConfig,
+) -> Result<SensitivityReport, ErlangError> {
+    validate_traffic(scenario.traffic)?;
+    if scenario.traffic == 0.0 {
+        return Err(ErlangError::InvalidTraffic(scenario.traffic));
+    }
+    if config
+        .traffic_deltas
+        .iter()
+        .any(|&delta| !(delta > -1.0 && delta.is_finite()))
+    {
+        return Err(ErlangError::InvalidParameter(
+            "traffic deltas must be finite and above -100%",
+        ));
+    }
+
+    let plan = dimension_trunks(
+        scenario.traffic,
+        scenario.blocking_probability,
+        config.channels_max,
+        scenario.trunk_type,
+    )?;
+    let bearer_channels = scenario.trunk_type.bearer_channels();
+    let installed_channels = plan
+        .spans
+        .checked_mul(bearer_channels)
+        .ok_or(ErlangError::NumericOverflow)?;
+    let blocking = erlang_b(scenario.traffic, installed_channels);
+    let blocking_derivative =
+        erlang_b_derivative_traffic(scenario.traffic, installed_channels as f64);
+    let max_traffic = max_traffic(installed_channels, scenario.blocking_probability)
+        .map_or(0.0, |inverse| inverse.traffic);
+
+    let mut rows = Vec::with_capacity(config.span_deltas.len());
+    for &span_delta in &config.span_deltas {
+        // Rows that would need fewer than zero spans are left out.
+        let Ok(spans) = u32::try_from(plan.spans as i64 + span_delta as i64) else {
+            continue;
+        };
+        let channels = spans
+            .checked_mul(bearer_channels)
+            .ok_or(ErlangError::NumericOverflow)?;
+        let cells = config
+            .traffic_deltas
+            .iter()
+            .map(|&delta| {
+                let traffic = scenario.traffic * (1.0 + delta);
+                let blocking = erlang_b(traffic, channels);
+                SensitivityCell {
+                    traffic,
+                    blocking,
+                    meets_target: blocking <= scenario.blocking_probability,
+                }
+            })
+            .collect();
+        rows.push(SensitivityRow {
+            span_delta,
+            spans,
+            channels,
+            cells,
+        });
+    }
+
+    Ok(SensitivityReport {
+        scenario: *scenario,
+        required_channels: plan.channels,
+        spans: plan.spans,
+        installed_channels,
+        blocking,
+        blocking_derivative,
+        // (A/B) × dB/dA = N − A + A × B for whole channels, without dividing by a blocking
+        // that can underflow to zero.
+        elasticity: installed_channels as f64 - scenario.traffic + scenario.traffic * blocking,
+        max_traffic,
+        headroom: (max_traffic - scenario.traffic).max(0.0),
+        traffic_deltas: config.traffic_deltas.clone(),
+        rows,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scenario(traffic: f64) -> Scenario {
+        Scenario {
+            traffic,
+            blocking_probability: 0.01,
+            trunk_type: TrunkType::E1Pri,
+        }
+    }
+
+    #[test]
+    fn test_operating_point() {
+        let report = sensitivity_report(&scenario(40.0), &SensitivityConfig::default()).unwrap();
+        assert_eq!(report.required_channels, 53);
+        assert_eq!(report.spans, 2);
+        assert_eq!(report.installed_channels, 60);
+        assert_eq!(report.blocking, erlang_b(40.0, 60));
+        // For whole channels the elasticity is N − A + A × B.
+        let expected = 60.0 - 40.0 + 40.0 * report.blocking;
+        assert!((report.elasticity - expected).abs() < 1e-9);
+        assert!(
+            (report.elasticity - 40.0 / report.blocking * report.blocking_derivative).abs() < 1e-6
+        );
+        // The headroom ends exactly at the target blocking.
+        assert!((erlang_b(40.0 + report.headroom, 60) - 0.01).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_grid() {
+        let report = sensitivity_report(&scenario(40.0), &SensitivityConfig::default()).unwrap();
+        let spans: Vec<u32> = report.rows.iter().map(|row| row.spans).collect();
+        assert_eq!(spans, [1, 2, 3]);
+        let base = &report.rows[1];
+        assert_eq!(base.cells[2].blocking, report.blocking);
+        assert!(base.cells[4].traffic > 47.99 && base.cells[4].traffic < 48.01);
+        // The spare channels absorb 10% growth but not 20%.
+        let meets: Vec<bool> = base.cells.iter().map(|cell| cell.meets_target).collect();
+        assert_eq!(meets, [true, true, true, true, false]);
+        // Dropping one E1 leaves 30 channels for 40 Erlangs.
+        assert!(report.rows[0].cells.iter().all(|cell| !cell.meets_target));
+        assert!(report.to_string().contains("   -20%"));
+        // Headings keep fractional percentages and drop binary rounding noise.
+        let config = SensitivityConfig {
+            traffic_deltas: vec![0.02, 0.025, 0.03, 0.07],
+            ..SensitivityConfig::default()
+        };
+        let text = sensitivity_report(&scenario(40.0), &config)
+            .unwrap()
+            .to_string();
+        assert!(text.contains("     +2%     +2.5%       +3%       +7%"));
+
+        // Rows that would need fewer than zero spans are left out.
+        let config = SensitivityConfig {
+            span_deltas: vec![-5, 0],
+            ..SensitivityConfig::default()
+        };
+        assert_eq!(
+            sensitivity_report(&scenario(40.0), &config)
+                .unwrap()
+                .rows
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_invalid_scenario() {
+        let config = SensitivityConfig::default();
+        assert_eq!(
+            sensitivity_report(&scenario(0.0), &config),
+            Err(ErlangError::InvalidTraffic(0.0))
+        );
+        let invalid = SensitivityConfig {
+            traffic_deltas: vec![-1.0],
+            ..SensitivityConfig::default()
+        };
+        assert!(sensitivity_report(&scenario(40.0), &invalid).is_err());
+        let overflow = SensitivityConfig {
+            span_deltas: vec![0, i32::MAX],
+            ..SensitivityConfig::default()
+        };
+        assert_eq!(
+            sensitivity_report(&scenario(40.0), &overflow),
+            Err(ErlangError::NumericOverflow)
+        );
+    }
+}
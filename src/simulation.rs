@@ -0,0 +1,362 @@
+// Discrete-event simulation of a loss system to validate Erlang B dimensioning.
+
+use crate::erlang_b;
+use crate::stats::{inverse_normal_cdf, student_t_quantile};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+/// Seedable xoshiro256** pseudo-random number generator.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    /// Creates a generator whose state is expanded from `seed` with SplitMix64.
+    pub fn new(seed: u64) -> Self {
+        let mut seed = seed;
+        let mut next = || {
+            seed = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        };
+        Rng {
+            state: [next(), next(), next(), next()],
+        }
+    }
+
+    /// Returns the next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.state[1] << 17;
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+        result
+    }
+
+    /// Returns a uniform sample in the open interval (0, 1).
+    pub fn next_f64(&mut self) -> f64 {
+        ((self.next_u64() >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+    }
+}
+
+/// Probability distributions for inter-arrival and holding times.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distribution {
+    /// Exponential (memoryless) with the given mean.
+    Exponential {
+        /// Mean value.
+        mean: f64,
+    },
+    /// A constant value.
+    Deterministic {
+        /// The value.
+        value: f64,
+    },
+    /// Lognormal with the given mean and standard deviation of the variable itself.
+    Lognormal {
+        /// Mean value.
+        mean: f64,
+        /// Standard deviation.
+        std_dev: f64,
+    },
+    /// Pareto (heavy-tailed) with the given shape (above 1) and mean.
+    Pareto {
+        /// Tail index; smaller values give heavier tails.
+        shape: f64,
+        /// Mean value.
+        mean: f64,
+    },
+    /// Two-phase hyperexponential with balanced means, for bursty arrivals with a
+    /// squared coefficient of variation of at least 1.
+    HyperExponential {
+        /// Mean value.
+        mean: f64,
+        /// Squared coefficient of variation (variance / mean²).
+        scv: f64,
+    },
+}
+
+impl Distribution {
+    /// Mean of the distribution.
+    pub fn mean(&self) -> f64 {
+        match *self {
+            Distribution::Exponential { mean }
+            | Distribution::Lognormal { mean, .. }
+            | Distribution::Pareto { mean, .. }
+            | Distribution::HyperExponential { mean, .. } => mean,
+            Distribution::Deterministic { value } => value,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        let positive = |value: f64| value.is_finite() && value > 0.0;
+        match *self {
+            Distribution::Exponential { mean } => positive(mean),
+            Distribution::Deterministic { value } => positive(value),
+            Distribution::Lognormal { mean, std_dev } => {
+                positive(mean) && std_dev.is_finite() && std_dev >= 0.0
+            }
+            Distribution::Pareto { shape, mean } => positive(mean) && shape > 1.0,
+            Distribution::HyperExponential { mean, scv } => positive(mean) && scv >= 1.0,
+        }
+    }
+
+    /// Draws one sample from the distribution.
+    pub fn sample(&self, rng: &mut Rng) -> f64 {
+        match *self {
+            Distribution::Exponential { mean } => -mean * rng.next_f64().ln(),
+            Distribution::Deterministic { value } => value,
+            Distribution::Lognormal { mean, std_dev } => {
+                let sigma2 = (1.0 + (std_dev / mean).powi(2)).ln();
+                let mu = mean.ln() - 0.5 * sigma2;
+                (mu + sigma2.sqrt() * inverse_normal_cdf(rng.next_f64())).exp()
+            }
+            Distribution::Pareto { shape, mean } => {
+                let scale = mean * (shape - 1.0) / shape;
+                scale / rng.next_f64().powf(1.0 / shape)
+            }
+            Distribution::HyperExponential { mean, scv } => {
+                let p = 0.5 * (1.0 + ((scv - 1.0) / (scv + 1.0)).sqrt());
+                let phase_mean = if rng.next_f64() < p {
+                    mean / (2.0 * p)
+                } else {
+                    mean / (2.0 * (1.0 - p))
+                };
+                -phase_mean * rng.next_f64().ln()
+            }
+        }
+    }
+}
+
+/// Settings of a loss-system simulation run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulationConfig {
+    /// The number of channels.
+    pub channels: u32,
+    /// Distribution of the time between call arrivals.
+    pub interarrival: Distribution,
+    /// Distribution of call holding times, in the same time unit.
+    pub holding: Distribution,
+    /// Number of measured call arrivals after the warm-up.
+    pub calls: u64,
+    /// Number of initial arrivals discarded to remove the empty-system bias.
+    pub warmup_calls: u64,
+    /// Number of batches for the batch-means confidence interval (at least 2).
+    pub batches: u32,
+    /// Two-sided confidence level of the interval, e.g. `0.95`.
+    pub confidence: f64,
+    /// Seed of the pseudo-random number generator.
+    pub seed: u64,
+}
+
+/// Measured blocking of a simulation run compared with the analytic value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationReport {
+    /// Measured call arrivals (after the warm-up).
+    pub offered_calls: u64,
+    /// Measured arrivals that found every channel busy.
+    pub blocked_calls: u64,
+    /// Measured blocking probability (mean of the batch means).
+    pub blocking: f64,
+    /// Half-width of the confidence interval around `blocking`.
+    pub half_width: f64,
+    /// Blocking probability of each batch.
+    pub batch_blocking: Vec<f64>,
+    /// Offered traffic in Erlangs: mean holding time / mean inter-arrival time.
+    pub offered_traffic: f64,
+    /// Erlang B blocking for the same offered traffic and channels.
+    pub analytic_blocking: f64,
+}
+
+impl SimulationReport {
+    /// Lower bound of the confidence interval.
+    pub fn lower(&self) -> f64 {
+        (self.blocking - self.half_width).max(0.0)
+    }
+
+    /// Upper bound of the confidence interval.
+    pub fn upper(&self) -> f64 {
+        (self.blocking + self.half_width).min(1.0)
+    }
+
+    /// Whether the analytic Erlang B value lies inside the confidence interval.
+    pub fn contains_analytic(&self) -> bool {
+        (self.lower()..=self.upper()).contains(&self.analytic_blocking)
+    }
+}
+
+/// Departure time ordered for use in a min-heap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Departure(f64);
+
+impl Eq for Departure {}
+
+impl PartialOrd for Departure {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Departure {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Simulates a loss system where calls finding every channel busy are cleared, and
+/// measures the blocking probability with batch-means statistics.
+///
+/// # Arguments
+/// * `config` - Channel count, distributions, run length and random seed.
+///
+/// # Returns
+/// The measured blocking with its confidence interval, or `None` if a distribution is
+/// invalid, fewer than 2 batches are requested, `calls` is smaller than `batches`, or
+/// `confidence` is outside (0, 1).
+pub fn simulate_loss_system(config: &SimulationConfig) -> Option<SimulationReport> {
+    if !config.interarrival.is_valid()
+        || !config.holding.is_valid()
+        || config.batches < 2
+        || config.calls < config.batches as u64
+        || !(config.confidence > 0.0 && config.confidence < 1.0)
+    {
+        return None;
+    }
+
+    let mut rng = Rng::new(config.seed);
+    let mut busy: BinaryHeap<Reverse<Departure>> = BinaryHeap::new();
+    let mut now = 0.0;
+
+    let batch_size = config.calls / config.batches as u64;
+    let measured_calls = batch_size * config.batches as u64;
+    let mut batch_blocked = vec![0u64; config.batches as usize];
+
+    for call in 0..config.warmup_calls + measured_calls {
+        now += config.interarrival.sample(&mut rng);
+        while busy.peek().is_some_and(|Reverse(end)| end.0 <= now) {
+            busy.pop();
+        }
+
+        if busy.len() < config.channels as usize {
+            let holding = config.holding.sample(&mut rng);
+            busy.push(Reverse(Departure(now + holding)));
+        } else if call >= config.warmup_calls {
+            let batch = ((call - config.warmup_calls) / batch_size) as usize;
+            batch_blocked[batch] += 1;
+        }
+    }
+
+    let batch_blocking: Vec<f64> = batch_blocked
+        .iter()
+        .map(|&blocked| blocked as f64 / batch_size as f64)
+        .collect();
+    let batches = batch_blocking.len() as f64;
+    let blocking = batch_blocking.iter().sum::<f64>() / batches;
+    let variance = batch_blocking
+        .iter()
+        .map(|value| (value - blocking).powi(2))
+        .sum::<f64>()
+        / (batches - 1.0);
+    let quantile = student_t_quantile(0.5 + 0.5 * config.confidence, batches - 1.0);
+
+    let offered_traffic = config.holding.mean() / config.interarrival.mean();
+    Some(SimulationReport {
+        offered_calls: measured_calls,
+        blocked_calls: batch_blocked.iter().sum(),
+        blocking,
+        half_width: quantile * (variance / batches).sqrt(),
+        batch_blocking,
+        offered_traffic,
+        analytic_blocking: erlang_b(offered_traffic, config.channels),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(holding: Distribution, interarrival: Distribution) -> SimulationConfig {
+        SimulationConfig {
+            channels: 12,
+            interarrival,
+            holding,
+            calls: 200_000,
+            warmup_calls: 5_000,
+            batches: 20,
+            confidence: 0.95,
+            seed: 42,
+        }
+    }
+
+    #[test]
+    fn test_rng_is_reproducible() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        for _ in 0..100 {
+            let value = a.next_f64();
+            assert_eq!(value, b.next_f64());
+            assert!(value > 0.0 && value < 1.0);
+        }
+        let mut rng = Rng::new(1);
+        let pareto = Distribution::Pareto {
+            shape: 2.5,
+            mean: 3.0,
+        };
+        let mean = (0..200_000).map(|_| pareto.sample(&mut rng)).sum::<f64>() / 200_000.0;
+        assert!((mean - 3.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_poisson_matches_erlang_b() {
+        // 10 Erlangs on 12 channels: blocking ~= 0.1197.
+        let report = simulate_loss_system(&config(
+            Distribution::Exponential { mean: 3.0 },
+            Distribution::Exponential { mean: 0.3 },
+        ))
+        .unwrap();
+        assert!((report.offered_traffic - 10.0).abs() < 1e-12);
+        assert!(report.contains_analytic(), "{report:?}");
+        assert_eq!(report.batch_blocking.len(), 20);
+    }
+
+    #[test]
+    fn test_lognormal_holding_is_insensitive() {
+        // Erlang B depends only on the mean holding time, not on its distribution.
+        let report = simulate_loss_system(&config(
+            Distribution::Lognormal {
+                mean: 3.0,
+                std_dev: 4.0,
+            },
+            Distribution::Exponential { mean: 0.3 },
+        ))
+        .unwrap();
+        assert!((report.blocking - report.analytic_blocking).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_bursty_arrivals_exceed_erlang_b() {
+        let report = simulate_loss_system(&config(
+            Distribution::Exponential { mean: 3.0 },
+            Distribution::HyperExponential {
+                mean: 0.3,
+                scv: 4.0,
+            },
+        ))
+        .unwrap();
+        assert!(report.lower() > report.analytic_blocking);
+
+        let mut invalid = config(
+            Distribution::Exponential { mean: 3.0 },
+            Distribution::Exponential { mean: 0.3 },
+        );
+        invalid.batches = 1;
+        assert!(simulate_loss_system(&invalid).is_none());
+    }
+}
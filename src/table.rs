@@ -0,0 +1,337 @@
+// Classic Erlang B tables: maximum traffic per channel count and grade of service.
+
+use crate::csv::join_fields;
+use crate::error::validate_blocking_probability;
+use crate::inverse::max_traffic;
+use crate::ErlangError;
+
+/// Output format for a rendered table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    /// GitHub-flavoured Markdown table.
+    Markdown,
+    /// Comma-separated values with a header row.
+    Csv,
+    /// Right-aligned fixed-width columns.
+    Text,
+    /// HTML `<table>` element.
+    Html,
+}
+
+/// Rows, columns and precision of an Erlang B table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableConfig {
+    /// First channel count (at least 1).
+    pub channels_from: u32,
+    /// Last channel count, inclusive.
+    pub channels_to: u32,
+    /// Blocking probabilities, one column each (between 0 and 1).
+    pub blocking_probabilities: Vec<f64>,
+    /// Decimal places shown for the traffic.
+    pub precision: usize,
+}
+
+impl Default for TableConfig {
+    /// 1 to 100 channels at 0.1%, 0.5%, 1%, 2% and 5%, to two decimal places, as in most
+    /// printed tables.
+    fn default() -> Self {
+        TableConfig {
+            channels_from: 1,
+            channels_to: 100,
+            blocking_probabilities: vec![0.001, 0.005, 0.01, 0.02, 0.05],
+            precision: 2,
+        }
+    }
+}
+
+/// One row of an Erlang B table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableRow {
+    /// Number of channels.
+    pub channels: u32,
+    /// Maximum traffic in Erlangs for each blocking probability, in column order.
+    pub traffic: Vec<f64>,
+}
+
+/// A computed Erlang B table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErlangBTable {
+    /// Blocking probabilities of the columns.
+    pub blocking_probabilities: Vec<f64>,
+    /// Decimal places shown when rendering.
+    pub precision: usize,
+    /// The rows, in increasing channel count.
+    pub rows: Vec<TableRow>,
+}
+
+/// Formats a fraction as a percentage column heading without trailing zeros, e.g. `0.5%`
+/// for `0.005`.
+pub(crate) fn percent_heading(fraction: f64) -> String {
+    let percent = format!("{:.6}", fraction * 100.0);
+    format!("{}%", percent.trim_end_matches('0').trim_end_matches('.'))
+}
+
+impl ErlangBTable {
+    /// The column headings: `N` followed by one percentage per blocking probability.
+    pub fn headings(&self) -> Vec<String> {
+        std::iter::once("N".to_string())
+            .chain(
+                self.blocking_probabilities
+                    .iter()
+                    .map(|&b| percent_heading(b)),
+            )
+            .collect()
+    }
+
+    /// The cells of each row as text, rounded to the table precision.
+    fn cells(&self) -> Vec<Vec<String>> {
+        self.rows
+            .iter()
+            .map(|row| {
+                std::iter::once(row.channels.to_string())
+                    .chain(
+                        row.traffic
+                            .iter()
+                            .map(|traffic| format!("{traffic:.*}", self.precision)),
+                    )
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Renders the table, rounding the traffic to the configured precision.
+    ///
+    /// # Arguments
+    /// * `format` - The output format.
+    ///
+    /// # Returns
+    /// The rendered table, ending with a newline.
+    pub fn render(&self, format: TableFormat) -> String {
+        render_table(&self.headings(), &self.cells(), format)
+    }
+}
+
+/// Escapes the characters with a meaning in HTML text and attribute values.
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escapes the pipes that would otherwise split a Markdown table cell.
+fn escape_markdown(value: &str) -> String {
+    value.replace('|', "\\|")
+}
+
+/// Renders headings and rows of text cells in one of the table formats. Every row must
+/// have one cell per heading.
+///
+/// # Arguments
+/// * `headings` - The column headings.
+/// * `rows` - The cells of each row, in column order.
+/// * `format` - The output format.
+///
+/// # Returns
+/// The rendered table, ending with a newline.
+pub fn render_table(headings: &[String], rows: &[Vec<String>], format: TableFormat) -> String {
+    match format {
+        TableFormat::Markdown => {
+            let line = |values: &[String]| {
+                let escaped: Vec<String> =
+                    values.iter().map(|value| escape_markdown(value)).collect();
+                format!("| {} |\n", escaped.join(" | "))
+            };
+            let mut output = line(headings);
+            output += &format!("|{}\n", "---:|".repeat(headings.len()));
+            for row in rows {
+                output += &line(row);
+            }
+            output
+        }
+        TableFormat::Csv => {
+            let mut output = join_fields(headings, ',') + "\n";
+            for row in rows {
+                output += &(join_fields(row, ',') + "\n");
+            }
+            output
+        }
+        TableFormat::Text => {
+            let widths: Vec<usize> = headings
+                .iter()
+                .enumerate()
+                .map(|(column, heading)| {
+                    rows.iter()
+                        .map(|row| row[column].len())
+                        .fold(heading.len(), usize::max)
+                })
+                .collect();
+            let line = |values: &[String]| {
+                let padded: Vec<String> = values
+                    .iter()
+                    .zip(&widths)
+                    .map(|(value, &width)| format!("{value:>width$}"))
+                    .collect();
+                padded.join("  ") + "\n"
+            };
+            let mut output = line(headings);
+            for row in rows {
+                output += &line(row);
+            }
+            output
+        }
+        TableFormat::Html => {
+            let mut output = String::from("<table>\n  <thead>\n    <tr>");
+            for heading in headings {
+                output += &format!("<th>{}</th>", escape_html(heading));
+            }
+            output += "</tr>\n  </thead>\n  <tbody>\n";
+            for row in rows {
+                output += "    <tr>";
+                for cell in row {
+                    output += &format!("<td>{}</td>", escape_html(cell));
+                }
+                output += "</tr>\n";
+            }
+            output + "  </tbody>\n</table>\n"
+        }
+    }
+}
+
+/// Generates an Erlang B table: the maximum traffic each channel count can be offered
+/// at each blocking probability, found with `inverse::max_traffic`.
+///
+/// # Arguments
+/// * `config` - The channel range, blocking probabilities and precision.
+///
+/// # Returns
+/// The table, `ErlangError::InvalidParameter` for an empty channel range or column set,
+/// `ErlangError::InvalidBlockingProbability`, or `ErlangError::NumericOverflow` if the
+/// inverse does not converge.
+pub fn erlang_b_table(config: &TableConfig) -> Result<ErlangBTable, ErlangError> {
+    if config.channels_from == 0 || config.channels_from > config.channels_to {
+        return Err(ErlangError::InvalidParameter(
+            "channel range must start at 1 or more and not be empty",
+        ));
+    }
+    if config.blocking_probabilities.is_empty() {
+        return Err(ErlangError::InvalidParameter(
+            "table needs at least one blocking probability",
+        ));
+    }
+    for &blocking_probability in &config.blocking_probabilities {
+        validate_blocking_probability(blocking_probability)?;
+    }
+
+    let rows = (config.channels_from..=config.channels_to)
+        .map(|channels| {
+            let traffic = config
+                .blocking_probabilities
+                .iter()
+                .map(|&blocking_probability| {
+                    max_traffic(channels, blocking_probability)
+                        .map(|inverse| inverse.traffic)
+                        .ok_or(ErlangError::NumericOverflow)
+                })
+                .collect::<Result<_, _>>()?;
+            Ok(TableRow { channels, traffic })
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(ErlangBTable {
+        blocking_probabilities: config.blocking_probabilities.clone(),
+        precision: config.precision,
+        rows,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_published_table() {
+        // Rows from standard printed Erlang B tables (0.1%, 0.5%, 1%, 2%, 5%).
+        let published = [
+            (5, ["0.76", "1.13", "1.36", "1.66", "2.22"]),
+            (10, ["3.09", "3.96", "4.46", "5.08", "6.22"]),
+            (30, ["16.68", "19.03", "20.34", "21.93", "24.80"]),
+            (100, ["75.24", "80.91", "84.06", "87.97", "95.24"]),
+        ];
+        let table = erlang_b_table(&TableConfig::default()).unwrap();
+        let cells = table.cells();
+        assert_eq!(cells.len(), 100);
+        for (channels, expected) in published {
+            assert_eq!(cells[channels - 1][1..], expected);
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let config = TableConfig {
+            channels_from: 9,
+            channels_to: 10,
+            blocking_probabilities: vec![0.005, 0.01],
+            precision: 3,
+        };
+        let table = erlang_b_table(&config).unwrap();
+        assert_eq!(
+            table.render(TableFormat::Markdown),
+            "| N | 0.5% | 1% |\n|---:|---:|---:|\n| 9 | 3.333 | 3.783 |\n| 10 | 3.961 | 4.461 |\n"
+        );
+        assert_eq!(
+            table.render(TableFormat::Csv),
+            "N,0.5%,1%\n9,3.333,3.783\n10,3.961,4.461\n"
+        );
+        assert_eq!(
+            table.render(TableFormat::Text),
+            " N   0.5%     1%\n 9  3.333  3.783\n10  3.961  4.461\n"
+        );
+        assert!(table
+            .render(TableFormat::Html)
+            .contains("<tr><td>10</td><td>3.961</td><td>4.461</td></tr>"));
+    }
+
+    #[test]
+    fn test_render_table() {
+        let headings = vec!["route".to_string(), "spans".to_string()];
+        let rows = vec![vec!["London, Paris".to_string(), "2".to_string()]];
+        assert_eq!(
+            render_table(&headings, &rows, TableFormat::Csv),
+            "route,spans\n\"London, Paris\",2\n"
+        );
+        assert_eq!(
+            render_table(&headings, &rows, TableFormat::Text),
+            "        route  spans\nLondon, Paris      2\n"
+        );
+        let rows = vec![vec!["<A&B> \"x|y\"".to_string(), "2".to_string()]];
+        assert_eq!(
+            render_table(&headings, &rows, TableFormat::Markdown),
+            "| route | spans |\n|---:|---:|\n| <A&B> \"x\\|y\" | 2 |\n"
+        );
+        assert!(render_table(&headings, &rows, TableFormat::Html)
+            .contains("<tr><td>&lt;A&amp;B&gt; &quot;x|y&quot;</td><td>2</td></tr>"));
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let empty = TableConfig {
+            channels_from: 10,
+            channels_to: 9,
+            ..TableConfig::default()
+        };
+        assert!(matches!(
+            erlang_b_table(&empty),
+            Err(ErlangError::InvalidParameter(_))
+        ));
+        let invalid = TableConfig {
+            blocking_probabilities: vec![0.01, 1.5],
+            ..TableConfig::default()
+        };
+        assert_eq!(
+            erlang_b_table(&invalid),
+            Err(ErlangError::InvalidBlockingProbability(1.5))
+        );
+    }
+}
@@ -0,0 +1,347 @@
+// Conversion of voice channels into E1/T1/J1 trunk spans with signalling timeslot accounting.
+
+use crate::{try_calculate_e1_channels, ErlangError};
+use std::fmt;
+use std::str::FromStr;
+
+/// Digital trunk types with their framing and signalling conventions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrunkType {
+    /// E1 ISDN PRI (30B+D): TS0 framing, TS16 D-channel, 30 bearer channels.
+    E1Pri,
+    /// E1 channel-associated signalling variant that frees TS16: 31 bearer channels.
+    E1Cas,
+    /// T1 ISDN PRI (23B+D): channel 24 carries the D-channel.
+    T1Pri,
+    /// T1 with robbed-bit channel-associated signalling: 24 bearer channels.
+    T1Cas,
+    /// Japanese J1 with channel-associated signalling: 24 bearer channels.
+    J1,
+}
+
+impl TrunkType {
+    /// Number of timeslots in a span that can carry bearer or signalling traffic,
+    /// excluding the E1 framing timeslot.
+    pub fn payload_timeslots(self) -> u32 {
+        match self {
+            TrunkType::E1Pri | TrunkType::E1Cas => 31,
+            TrunkType::T1Pri | TrunkType::T1Cas | TrunkType::J1 => 24,
+        }
+    }
+
+    /// Number of timeslots per span reserved for common-channel signalling.
+    pub fn signalling_timeslots(self) -> u32 {
+        match self {
+            TrunkType::E1Pri | TrunkType::T1Pri => 1,
+            TrunkType::E1Cas | TrunkType::T1Cas | TrunkType::J1 => 0,
+        }
+    }
+
+    /// Number of bearer (voice) channels per span.
+    pub fn bearer_channels(self) -> u32 {
+        self.payload_timeslots() - self.signalling_timeslots()
+    }
+
+    /// Whether several spans of this type can share one D-channel (NFAS).
+    pub fn supports_nfas(self) -> bool {
+        matches!(self, TrunkType::E1Pri | TrunkType::T1Pri)
+    }
+}
+
+impl fmt::Display for TrunkType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TrunkType::E1Pri => "e1-pri",
+            TrunkType::E1Cas => "e1-cas",
+            TrunkType::T1Pri => "t1-pri",
+            TrunkType::T1Cas => "t1-cas",
+            TrunkType::J1 => "j1",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for TrunkType {
+    type Err = ErlangError;
+
+    /// Parses a trunk type name such as `e1-pri`, `E1_CAS`, `t1pri` or `j1`.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let normalized: String = name
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_' | ' '))
+            .collect::<String>()
+            .to_ascii_lowercase();
+        match normalized.as_str() {
+            "e1pri" => Ok(TrunkType::E1Pri),
+            "e1cas" => Ok(TrunkType::E1Cas),
+            "t1pri" => Ok(TrunkType::T1Pri),
+            "t1cas" => Ok(TrunkType::T1Cas),
+            "j1" => Ok(TrunkType::J1),
+            _ => Err(ErlangError::InvalidParameter(
+                "unknown trunk type (expected e1-pri, e1-cas, t1-pri, t1-cas or j1)",
+            )),
+        }
+    }
+}
+
+/// Non-Facility Associated Signalling configuration, where one D-channel
+/// controls several PRI spans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NfasConfig {
+    /// Maximum number of spans controlled by one D-channel.
+    pub spans_per_group: u32,
+    /// Whether each group with two or more spans carries a backup D-channel.
+    pub backup_d_channel: bool,
+}
+
+/// Span requirement for carrying a number of voice channels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrunkPlan {
+    /// The trunk type used.
+    pub trunk_type: TrunkType,
+    /// Requested number of voice channels.
+    pub channels: u32,
+    /// Number of spans required.
+    pub spans: u32,
+    /// Total bearer channels provided by the spans.
+    pub bearer_capacity: u32,
+    /// Timeslots reserved for signalling across all spans.
+    pub signalling_timeslots: u32,
+    /// Timeslots in use: the requested channels plus signalling.
+    pub timeslots_used: u32,
+    /// Unused bearer channels, all of which are in the last span.
+    pub spare_channels: u32,
+}
+
+impl TrunkPlan {
+    fn new(
+        trunk_type: TrunkType,
+        channels: u32,
+        spans: u32,
+        signalling_timeslots: u32,
+    ) -> Result<Self, ErlangError> {
+        let bearer_capacity = spans
+            .checked_mul(trunk_type.payload_timeslots())
+            .and_then(|timeslots| timeslots.checked_sub(signalling_timeslots))
+            .ok_or(ErlangError::NumericOverflow)?;
+        Ok(TrunkPlan {
+            trunk_type,
+            channels,
+            spans,
+            bearer_capacity,
+            signalling_timeslots,
+            timeslots_used: channels + signalling_timeslots,
+            spare_channels: bearer_capacity - channels,
+        })
+    }
+}
+
+/// Calculates the number of spans needed to carry a number of voice channels.
+///
+/// # Arguments
+/// * `channels` - The number of voice channels, e.g. from `calculate_e1_channels`.
+/// * `trunk_type` - The trunk type providing the spans.
+///
+/// # Returns
+/// The span count with timeslot usage and spare capacity, or
+/// `ErlangError::NumericOverflow` if the timeslots of the spans do not fit in a `u32`.
+pub fn spans_for_channels(channels: u32, trunk_type: TrunkType) -> Result<TrunkPlan, ErlangError> {
+    let spans = channels.div_ceil(trunk_type.bearer_channels());
+    TrunkPlan::new(
+        trunk_type,
+        channels,
+        spans,
+        spans * trunk_type.signalling_timeslots(),
+    )
+}
+
+/// Calculates the number of PRI spans needed to carry a number of voice channels when
+/// spans are grouped under shared D-channels (NFAS).
+///
+/// Only the first span of each group (and the second, with a backup D-channel) gives up
+/// a timeslot for signalling; the remaining spans carry bearer traffic on every timeslot.
+/// The span count follows in closed form from the channels of a full group.
+///
+/// # Arguments
+/// * `channels` - The number of voice channels.
+/// * `trunk_type` - A PRI trunk type (`E1Pri` or `T1Pri`).
+/// * `nfas` - The NFAS grouping.
+///
+/// # Returns
+/// The span count with timeslot usage and spare capacity,
+/// `ErlangError::InvalidParameter` if the trunk type does not support NFAS or
+/// `spans_per_group` is zero, or `ErlangError::NumericOverflow` if the timeslots of the
+/// spans do not fit in a `u32`.
+pub fn spans_for_channels_nfas(
+    channels: u32,
+    trunk_type: TrunkType,
+    nfas: NfasConfig,
+) -> Result<TrunkPlan, ErlangError> {
+    if !trunk_type.supports_nfas() {
+        return Err(ErlangError::InvalidParameter(
+            "NFAS needs a PRI trunk type (e1-pri or t1-pri)",
+        ));
+    }
+    if nfas.spans_per_group == 0 {
+        return Err(ErlangError::InvalidParameter(
+            "NFAS groups need at least one span",
+        ));
+    }
+
+    let payload = trunk_type.payload_timeslots();
+    let d_channels = if nfas.backup_d_channel { 2 } else { 1 }.min(nfas.spans_per_group);
+    let group_channels = nfas
+        .spans_per_group
+        .checked_mul(payload)
+        .ok_or(ErlangError::NumericOverflow)?
+        - d_channels;
+
+    // Whole groups first, then the spans of the last group: those with a D-channel carry
+    // one channel fewer than the rest.
+    let full_groups = channels / group_channels;
+    let remainder = channels % group_channels;
+    let signalling_capacity = d_channels * (payload - 1);
+    let last_group_spans = if remainder <= signalling_capacity {
+        remainder.div_ceil(payload - 1)
+    } else {
+        d_channels + (remainder - signalling_capacity).div_ceil(payload)
+    };
+    let spans = full_groups
+        .checked_mul(nfas.spans_per_group)
+        .and_then(|spans| spans.checked_add(last_group_spans))
+        .ok_or(ErlangError::NumericOverflow)?;
+    let signalling_timeslots = full_groups * d_channels + d_channels.min(last_group_spans);
+
+    TrunkPlan::new(trunk_type, channels, spans, signalling_timeslots)
+}
+
+/// Calculates the spans required to carry a traffic load at a given blocking probability.
+///
+/// # Arguments
+/// * `traffic` - The traffic load in Erlangs.
+/// * `blocking_probability` - Desired blocking probability (between 0 and 1).
+/// * `channels_max` - Maximum number of channels to search for.
+/// * `trunk_type` - The trunk type providing the spans.
+///
+/// # Returns
+/// The span plan, any error from `try_calculate_e1_channels`, or
+/// `ErlangError::NumericOverflow` from `spans_for_channels`.
+pub fn dimension_trunks(
+    traffic: f64,
+    blocking_probability: f64,
+    channels_max: u32,
+    trunk_type: TrunkType,
+) -> Result<TrunkPlan, ErlangError> {
+    let channels = try_calculate_e1_channels(traffic, blocking_probability, channels_max)?;
+    spans_for_channels(channels, trunk_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bearer_channels() {
+        assert_eq!(TrunkType::E1Pri.bearer_channels(), 30);
+        assert_eq!(TrunkType::E1Cas.bearer_channels(), 31);
+        assert_eq!(TrunkType::T1Pri.bearer_channels(), 23);
+        assert_eq!(TrunkType::T1Cas.bearer_channels(), 24);
+        assert_eq!(TrunkType::J1.bearer_channels(), 24);
+    }
+
+    #[test]
+    fn test_trunk_type_names() {
+        for trunk_type in [
+            TrunkType::E1Pri,
+            TrunkType::E1Cas,
+            TrunkType::T1Pri,
+            TrunkType::T1Cas,
+            TrunkType::J1,
+        ] {
+            assert_eq!(trunk_type.to_string().parse(), Ok(trunk_type));
+        }
+        assert_eq!("E1_PRI".parse(), Ok(TrunkType::E1Pri));
+        assert!("e2".parse::<TrunkType>().is_err());
+    }
+
+    #[test]
+    fn test_spans_for_channels() {
+        let plan = spans_for_channels(61, TrunkType::E1Pri).unwrap();
+        assert_eq!(plan.spans, 3);
+        assert_eq!(plan.bearer_capacity, 90);
+        assert_eq!(plan.signalling_timeslots, 3);
+        assert_eq!(plan.timeslots_used, 64);
+        assert_eq!(plan.spare_channels, 29);
+
+        assert_eq!(spans_for_channels(62, TrunkType::E1Cas).unwrap().spans, 2);
+        assert_eq!(spans_for_channels(0, TrunkType::T1Pri).unwrap().spans, 0);
+        assert_eq!(
+            spans_for_channels(u32::MAX, TrunkType::E1Pri),
+            Err(ErlangError::NumericOverflow)
+        );
+    }
+
+    #[test]
+    fn test_spans_for_channels_nfas() {
+        // Four T1 spans under one D-channel carry 23 + 3 × 24 = 95 channels.
+        let nfas = NfasConfig {
+            spans_per_group: 4,
+            backup_d_channel: false,
+        };
+        let plan = spans_for_channels_nfas(95, TrunkType::T1Pri, nfas).unwrap();
+        assert_eq!(plan.spans, 4);
+        assert_eq!(plan.signalling_timeslots, 1);
+        assert_eq!(plan.spare_channels, 0);
+        assert_eq!(spans_for_channels(95, TrunkType::T1Pri).unwrap().spans, 5);
+
+        let backup = NfasConfig {
+            backup_d_channel: true,
+            ..nfas
+        };
+        let plan = spans_for_channels_nfas(95, TrunkType::T1Pri, backup).unwrap();
+        assert_eq!(plan.spans, 5);
+        assert_eq!(plan.signalling_timeslots, 3);
+
+        assert!(matches!(
+            spans_for_channels_nfas(95, TrunkType::T1Cas, nfas),
+            Err(ErlangError::InvalidParameter(_))
+        ));
+        assert_eq!(
+            spans_for_channels_nfas(u32::MAX, TrunkType::T1Pri, nfas),
+            Err(ErlangError::NumericOverflow)
+        );
+
+        // The closed form agrees with adding spans one at a time.
+        for nfas in [
+            nfas,
+            backup,
+            NfasConfig {
+                spans_per_group: 1,
+                backup_d_channel: true,
+            },
+        ] {
+            for trunk_type in [TrunkType::E1Pri, TrunkType::T1Pri] {
+                for channels in 0..400 {
+                    let plan = spans_for_channels_nfas(channels, trunk_type, nfas).unwrap();
+                    assert!(plan.bearer_capacity >= channels);
+                    if plan.spans > 0 {
+                        let fewer = plan.spans - 1;
+                        let groups = fewer / nfas.spans_per_group;
+                        let d_channels =
+                            if nfas.backup_d_channel { 2 } else { 1 }.min(nfas.spans_per_group);
+                        let signalling =
+                            groups * d_channels + d_channels.min(fewer % nfas.spans_per_group);
+                        assert!(fewer * trunk_type.payload_timeslots() - signalling < channels);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dimension_trunks() {
+        let plan = dimension_trunks(50.0, 0.01, 1_000, TrunkType::E1Pri).unwrap();
+        assert_eq!(plan.channels, 64);
+        assert_eq!(plan.spans, 3);
+    }
+}